@@ -18,6 +18,7 @@ fn bench_return_free_idx(b: &mut Bencher) {
     let mut map = EmptyMap::new();
     let idx = map.get_empty_idx();
     b.iter(|| {
-            test::black_box(map.return_free_idx(idx));
+            map.return_free_idx(idx);
+            test::black_box(());
     });
 }
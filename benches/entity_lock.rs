@@ -0,0 +1,67 @@
+//! Measures how reads of shared entities scale with the number of threads.
+//!
+//! Run with `cargo bench --bench entity_lock`. Every thread reads random
+//! entities of a shared line, either through the read lock or through the write
+//! lock, which behaves like the spin lock entities used to have. Every
+//! `WRITE_EVERY`th access is a write.
+
+use std::hint::black_box;
+use std::sync::Arc;
+use std::thread;
+use std::time::Instant;
+
+use owl::core_owl::node::data_line::entity::{Entity, Link};
+
+/// Number of entities shared by the threads.
+const ENTITIES: u64 = 64;
+
+/// Accesses made by every thread.
+const ACCESSES: u64 = 2_000_000;
+
+/// One access in `WRITE_EVERY` is a write.
+const WRITE_EVERY: u64 = 100;
+
+/// Thread counts compared.
+const THREADS: [u64; 4] = [1, 2, 4, 8];
+
+/// Runs `threads` threads over the same entities and returns millions of
+/// accesses per second.
+fn run(threads: u64, shared_reads: bool) -> f64 {
+    let line: Arc<Vec<Entity<u64, u64>>> =
+        Arc::new((0..ENTITIES).map(|key| Entity::new(key, key, Link::default(), Link::default())).collect());
+    let start = Instant::now();
+    let workers: Vec<_> = (0..threads)
+        .map(|thread| {
+            let line = Arc::clone(&line);
+            thread::spawn(move || {
+                let mut state = thread.wrapping_mul(0x9E37_79B9_7F4A_7C15) | 1;
+                for access in 0..ACCESSES {
+                    state ^= state << 13;
+                    state ^= state >> 7;
+                    state ^= state << 17;
+                    let entity = &line[(state % ENTITIES) as usize];
+                    match (access % WRITE_EVERY == 0, shared_reads) {
+                        (true, _) => *entity.lock().value_mut() += 1,
+                        (false, true) => {
+                            black_box(*entity.read());
+                        }
+                        (false, false) => {
+                            black_box(*entity.lock().as_ref());
+                        }
+                    }
+                }
+            })
+        })
+        .collect();
+    for worker in workers {
+        worker.join().unwrap();
+    }
+    (threads * ACCESSES) as f64 / start.elapsed().as_secs_f64() / 1e6
+}
+
+fn main() {
+    println!("{:<10}{:>18}{:>18}", "threads", "rw lock Mops/s", "exclusive Mops/s");
+    for threads in THREADS {
+        println!("{:<10}{:>18.1}{:>18.1}", threads, run(threads, true), run(threads, false));
+    }
+}
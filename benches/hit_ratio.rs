@@ -0,0 +1,90 @@
+//! Compares the hit ratio of the eviction policies of a single `Node`.
+//!
+//! Run with `cargo bench --bench hit_ratio`. Every trace is replayed against a
+//! fresh node per policy: a hit is a successful `get`, a miss is followed by an
+//! `insert` of the key.
+
+use owl::core_owl::node::{
+    admission::TinyLfu,
+    node::Node,
+    node_impl::NodeImpl,
+    policy::{Clock, EvictionPolicy, Fifo, Lru, S3Fifo, Sieve},
+};
+use xxhash_rust::xxh3::xxh3_64;
+
+/// Number of accesses in every trace.
+const ACCESSES: usize = 2_000_000;
+
+/// Number of distinct keys of the skewed part of the traces.
+const UNIVERSE: u64 = 400_000;
+
+/// Minimal xorshift64* generator, so traces are identical between runs.
+struct XorShift(u64);
+
+impl XorShift {
+    fn next_f64(&mut self) -> f64 {
+        self.0 ^= self.0 >> 12;
+        self.0 ^= self.0 << 25;
+        self.0 ^= self.0 >> 27;
+        (self.0.wrapping_mul(0x2545_F491_4F6C_DD1D) >> 11) as f64 / (1u64 << 53) as f64
+    }
+}
+
+/// Skewed accesses: key `UNIVERSE * u^skew` for a uniform `u`, so low keys are hot.
+fn skewed(rng: &mut XorShift, skew: f64) -> u64 {
+    (UNIVERSE as f64 * rng.next_f64().powf(skew)) as u64
+}
+
+/// Builds a skewed trace, interrupted every `scan_every` accesses by a scan of
+/// `scan_len` keys that are never seen again.
+fn trace(skew: f64, scan_every: usize, scan_len: usize) -> Vec<u64> {
+    let mut rng = XorShift(0x005E_ED0F_0A71);
+    let mut scan_key = UNIVERSE;
+    let mut trace = Vec::with_capacity(ACCESSES);
+    while trace.len() < ACCESSES {
+        if scan_every != 0 && trace.len() % scan_every == 0 {
+            trace.extend(scan_key..scan_key + scan_len as u64);
+            scan_key += scan_len as u64;
+        }
+        trace.push(skewed(&mut rng, skew));
+    }
+    trace.truncate(ACCESSES);
+    trace
+}
+
+/// Replays `trace` on `node` and returns the hit ratio.
+fn replay<P: EvictionPolicy<u64, u64>>(mut node: Node<u64, u64, P>, trace: &[u64]) -> f64 {
+    let mut hits = 0usize;
+    for &key in trace {
+        let hash = xxh3_64(&key.to_le_bytes());
+        match node.get(hash, &key) {
+            Some(_) => hits += 1,
+            None => {
+                node.insert(hash, key, key);
+            }
+        }
+    }
+    hits as f64 / trace.len() as f64
+}
+
+fn main() {
+    let traces = [
+        ("skewed", trace(3.0, 0, 0)),
+        ("mildly skewed", trace(1.5, 0, 0)),
+        ("skewed + scans", trace(3.0, 100_000, 50_000)),
+    ];
+
+    println!("{:<16}{:>10}{:>10}{:>10}{:>10}{:>10}{:>14}", "trace", "lru", "fifo", "clock", "sieve", "s3-fifo", "lru+tinylfu");
+    for (name, trace) in &traces {
+        println!(
+            "{:<16}{:>10.4}{:>10.4}{:>10.4}{:>10.4}{:>10.4}{:>14.4}",
+            name,
+            replay(Node::<u64, u64, Lru>::new(), trace),
+            replay(Node::<u64, u64, Fifo>::new(), trace),
+            replay(Node::<u64, u64, Clock>::new(), trace),
+            replay(Node::<u64, u64, Sieve>::new(), trace),
+            replay(Node::<u64, u64, S3Fifo>::new(), trace),
+            replay(Node::<u64, u64, Lru>::new().with_admission(TinyLfu::new()), trace),
+        );
+    }
+}
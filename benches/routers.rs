@@ -0,0 +1,52 @@
+//! Compares the routers an `Owl` can shard its keys with.
+//!
+//! Run with `cargo bench --bench routers`. For every node count, reports the
+//! share of keys moved when a node is added or removed, the ratio between the
+//! most loaded node and the mean, and the mean lookup time.
+
+use std::hint::black_box;
+use std::time::Instant;
+
+use owl::core_owl::owl_ring::Ring;
+use owl::core_owl::router::{JumpHash, Maglev, Rendezvous, Router};
+use xxhash_rust::xxh3::xxh3_64;
+
+/// Number of hashes routed for every measure.
+const KEYS: u64 = 200_000;
+
+/// Node counts compared.
+const NODES: [usize; 5] = [2, 8, 32, 128, 512];
+
+/// Returns the share of `hashes` moved by growing and by shrinking `router`
+/// by one node, its maximum over mean load, and its lookup time in nanoseconds.
+fn measure<R: Router>(router: &R, hashes: &[u64]) -> (f64, f64, f64, f64) {
+    let nodes = router.nodes();
+    let grow = router.moved_keys(nodes + 1, hashes) as f64 / hashes.len() as f64;
+    let shrink = router.moved_keys(nodes - 1, hashes) as f64 / hashes.len() as f64;
+
+    let mut loads = vec![0usize; nodes];
+    let start = Instant::now();
+    for &hash_val in hashes {
+        loads[black_box(router.route(black_box(hash_val)))] += 1;
+    }
+    let lookup = start.elapsed().as_nanos() as f64 / hashes.len() as f64;
+    let skew = *loads.iter().max().unwrap_or(&0) as f64 * nodes as f64 / hashes.len() as f64;
+    (grow, shrink, skew, lookup)
+}
+
+fn main() {
+    let hashes: Vec<u64> = (0..KEYS).map(|key| xxh3_64(&key.to_le_bytes())).collect();
+
+    println!("{:<8}{:<12}{:>10}{:>10}{:>10}{:>12}", "nodes", "router", "grow", "shrink", "skew", "lookup ns");
+    for nodes in NODES {
+        let rows = [
+            ("ring", measure(&Ring::new(nodes), &hashes)),
+            ("jump", measure(&JumpHash::new(nodes), &hashes)),
+            ("rendezvous", measure(&Rendezvous::new(nodes), &hashes)),
+            ("maglev", measure(&Maglev::new(nodes), &hashes)),
+        ];
+        for (name, (grow, shrink, skew, lookup)) in rows {
+            println!("{:<8}{:<12}{:>10.4}{:>10.4}{:>10.3}{:>12.1}", nodes, name, grow, shrink, skew, lookup);
+        }
+    }
+}
@@ -0,0 +1,78 @@
+//! Compares a node chaining its entities from a `HashLine` with a node indexing
+//! them in a `TagLine` instead.
+//!
+//! Run with `cargo bench --bench tag_line`. A node is filled to 50%, 90% and 99%
+//! of its slots, then every key is looked up once, as well as as many absent
+//! keys. Writes are timed too: every key is deleted and inserted again, which
+//! links and unlinks chains on one side and updates the tag table on the other.
+
+use std::hint::black_box;
+use std::time::Instant;
+
+use owl::core_owl::node::{node::Node, node_impl::NodeImpl, ARR_SIZE};
+use xxhash_rust::xxh3::xxh3_64;
+
+/// Filling ratios compared.
+const LOADS: [f64; 3] = [0.5, 0.9, 0.99];
+
+/// Times every key of `hashes` is looked up, or deleted and inserted again.
+const ROUNDS: usize = 20;
+
+/// Mean nanoseconds per operation of one layout.
+struct Timings {
+    hit: f64,
+    miss: f64,
+    churn: f64,
+}
+
+/// Returns the mean nanoseconds per lookup of `hashes` in `node`.
+fn lookups(node: &Node<u64, u64>, hashes: &[(u64, u64)]) -> f64 {
+    let start = Instant::now();
+    for _ in 0..ROUNDS {
+        for (key, hash) in hashes {
+            black_box(node.handle(*hash, key));
+        }
+    }
+    start.elapsed().as_nanos() as f64 / (ROUNDS * hashes.len()) as f64
+}
+
+/// Returns the mean nanoseconds per delete and insert of a key of `hashes`,
+/// all of them stored in `node`.
+fn churn(node: &mut Node<u64, u64>, hashes: &[(u64, u64)]) -> f64 {
+    let start = Instant::now();
+    for _ in 0..ROUNDS {
+        for &(key, hash) in hashes {
+            black_box(node.delete(hash, &key));
+            node.insert(hash, key, key);
+        }
+    }
+    start.elapsed().as_nanos() as f64 / (ROUNDS * hashes.len()) as f64
+}
+
+/// Fills a node with `present` and times lookups of present and absent keys,
+/// then writes.
+fn measure(mut node: Node<u64, u64>, present: &[(u64, u64)], absent: &[(u64, u64)]) -> Timings {
+    for &(key, hash) in present {
+        node.insert(hash, key, key);
+    }
+    let (hit, miss) = (lookups(&node, present), lookups(&node, absent));
+    Timings { hit, miss, churn: churn(&mut node, present) }
+}
+
+fn main() {
+    println!(
+        "{:<8}{:>12}{:>12}{:>12}{:>12}{:>12}{:>12}",
+        "load", "chain hit", "tags hit", "chain miss", "tags miss", "chain churn", "tags churn"
+    );
+    for load in LOADS {
+        let count = (ARR_SIZE as f64 * load) as u64;
+        let keyed = |keys: std::ops::Range<u64>| keys.map(|key| (key, xxh3_64(&key.to_le_bytes()))).collect::<Vec<_>>();
+        let (present, absent) = (keyed(0..count), keyed(1 << 40..(1 << 40) + count));
+        let chains = measure(Node::new(), &present, &absent);
+        let tags = measure(Node::new().with_tag_line(), &present, &absent);
+        println!(
+            "{:<8}{:>12.2}{:>12.2}{:>12.2}{:>12.2}{:>12.2}{:>12.2}",
+            load, chains.hit, tags.hit, chains.miss, tags.miss, chains.churn, tags.churn
+        );
+    }
+}
@@ -1,5 +0,0 @@
-#![feature(portable_simd)]
-#![feature(ptr_as_ref_unchecked)]
-
-pub mod core_owl;
-// pub mod owl;
\ No newline at end of file
@@ -0,0 +1,10 @@
+//! Hashing of the keys.
+//!
+//! An `Owl` hashes every key once with a `BuildHasher`, by default an
+//! `XXBuildHasher`, whose per instance random seed keeps keys picked by an
+//! attacker from piling up in one `HashLine` bucket.
+
+mod xx_hasher;
+mod test;
+
+pub use xx_hasher::{XXBuildHasher, XXHasher};
@@ -0,0 +1,42 @@
+#[test]
+pub fn xx_hasher1_every_write_counts(){
+    use std::hash::BuildHasher;
+    use super::XXBuildHasher;
+
+    let hasher = XXBuildHasher::with_seed(10_273);
+    // Keys differing only in a field hashed before the last one.
+    assert_ne!(hasher.hash_one((1u64, 2u64)), hasher.hash_one((3u64, 2u64)));
+    assert_ne!(hasher.hash_one(("a", "b")), hasher.hash_one(("c", "b")));
+    assert_eq!(hasher.hash_one((1u64, 2u64)), hasher.hash_one((1u64, 2u64)));
+}
+
+#[test]
+pub fn xx_hasher2_streamed_like_one_shot(){
+    use std::hash::Hasher;
+    use xxhash_rust::xxh3::xxh3_64_with_seed;
+    use super::XXHasher;
+
+    // Inputs around the buffered length, written in pieces, hash like xxh3 of
+    // the whole input.
+    let bytes: Vec<u8> = (0..300u32).map(|byte| (byte * 7) as u8).collect();
+    for len in [0, 1, 8, 63, 64, 65, 128, 240, 241, 300]{
+        for piece in [1, 5, 64, 300]{
+            let mut hasher = XXHasher::with_seed(42);
+            bytes[..len].chunks(piece).for_each(|chunk| hasher.write(chunk));
+            assert_eq!(hasher.finish(), xxh3_64_with_seed(&bytes[..len], 42));
+        }
+    }
+}
+
+#[test]
+pub fn xx_hasher3_random_seeds(){
+    use std::hash::BuildHasher;
+    use super::XXBuildHasher;
+
+    let (first, second) = (XXBuildHasher::new(), XXBuildHasher::new());
+    assert_ne!(first.seed(), second.seed());
+    assert_ne!(first.hash_one(7u64), second.hash_one(7u64));
+    // Clones share the seed of their builder.
+    assert_eq!(first.clone().hash_one(7u64), first.hash_one(7u64));
+    assert_eq!(XXBuildHasher::with_seed(1), XXBuildHasher::with_seed(1));
+}
@@ -0,0 +1,108 @@
+use std::collections::hash_map::RandomState;
+use std::hash::{BuildHasher, Hasher};
+
+use xxhash_rust::xxh3::{xxh3_64_with_seed, Xxh3};
+
+/// Bytes buffered before the hasher switches to the streaming state.
+const INLINE: usize = 64;
+
+/// A streaming xxh3 `Hasher`.
+///
+/// Every `write` is part of the hash: the hash of a key is the seeded xxh3 of
+/// all the bytes written, in order. Keys short enough to be buffered, which is
+/// most of them, are hashed in one shot by `finish`; longer ones are fed to
+/// xxh3's streaming state, which gives the same hash.
+#[derive(Clone)]
+pub struct XXHasher {
+    /// Seed of the hash.
+    seed: u64,
+
+    /// Bytes written so far, until the stream takes over.
+    buf: [u8; INLINE],
+
+    /// Number of bytes of `buf` in use.
+    len: usize,
+
+    /// Streaming state, once more than `INLINE` bytes were written.
+    stream: Option<Box<Xxh3>>,
+}
+
+/// Builds `XXHasher`s sharing a seed drawn at random for every instance.
+///
+/// Clones share the seed, so they hash keys the same way.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct XXBuildHasher {
+    /// Seed of every hasher built.
+    seed: u64,
+}
+
+impl XXHasher {
+    /// Creates a hasher of seed `seed` that has hashed nothing yet.
+    pub fn with_seed(seed: u64) -> Self {
+        XXHasher {
+            seed,
+            buf: [0; INLINE],
+            len: 0,
+            stream: None,
+        }
+    }
+}
+
+impl Hasher for XXHasher {
+    #[inline(always)]
+    fn write(&mut self, bytes: &[u8]) {
+        if let Some(stream) = &mut self.stream {
+            stream.update(bytes);
+            return;
+        }
+        if self.len + bytes.len() <= INLINE {
+            self.buf[self.len..self.len + bytes.len()].copy_from_slice(bytes);
+            self.len += bytes.len();
+            return;
+        }
+        let mut stream = Box::new(Xxh3::with_seed(self.seed));
+        stream.update(&self.buf[..self.len]);
+        stream.update(bytes);
+        self.stream = Some(stream);
+    }
+
+    #[inline(always)]
+    fn finish(&self) -> u64 {
+        match &self.stream {
+            Some(stream) => stream.digest(),
+            None => xxh3_64_with_seed(&self.buf[..self.len], self.seed),
+        }
+    }
+}
+
+impl XXBuildHasher {
+    /// Creates a builder with a seed drawn from the process' random state.
+    pub fn new() -> Self {
+        Self::with_seed(RandomState::new().hash_one(0u64))
+    }
+
+    /// Creates a builder with a fixed seed, useful for reproducible runs.
+    pub fn with_seed(seed: u64) -> Self {
+        XXBuildHasher { seed }
+    }
+
+    /// Returns the seed of every hasher built.
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+}
+
+impl Default for XXBuildHasher {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl BuildHasher for XXBuildHasher {
+    type Hasher = XXHasher;
+
+    #[inline(always)]
+    fn build_hasher(&self) -> XXHasher {
+        XXHasher::with_seed(self.seed)
+    }
+}
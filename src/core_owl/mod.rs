@@ -1,2 +1,5 @@
+pub mod hash;
 pub mod owl_ring;
-pub mod node;
\ No newline at end of file
+pub mod node;
+pub mod owl;
+pub mod router;
@@ -0,0 +1,59 @@
+/// A small bloom filter placed in front of the sketch.
+///
+/// Most keys of a scan are seen exactly once. The doorkeeper absorbs those first
+/// sightings so that they never reach, and never pollute, the count-min sketch.
+#[derive(Debug)]
+pub struct Doorkeeper {
+    /// Bit set of the filter.
+    bits: Box<[u64]>,
+
+    /// Number of bits minus one, the number of bits being a power of two.
+    mask: usize,
+}
+
+impl Doorkeeper {
+    /// Creates an empty filter of at least `bits` bits.
+    ///
+    /// # Arguments
+    /// * `bits` - Size of the filter, rounded up to a power of two of at least 64.
+    pub fn new(bits: usize) -> Self {
+        let bits = bits.max(64).next_power_of_two();
+        Doorkeeper {
+            bits: vec![0; bits / 64].into_boxed_slice(),
+            mask: bits - 1,
+        }
+    }
+
+    /// Returns the two bit positions of `hash`.
+    #[inline(always)]
+    fn positions(&self, hash: u64) -> [usize; 2] {
+        let second = hash.rotate_left(32).wrapping_mul(0x9E37_79B9_7F4A_7C15);
+        [hash as usize & self.mask, second as usize & self.mask]
+    }
+
+    /// Returns `true` if `hash` was probably inserted since the last clear.
+    pub fn contains(&self, hash: u64) -> bool {
+        self.positions(hash)
+            .iter()
+            .all(|bit| self.bits[bit >> 6] & (1 << (bit & 63)) != 0)
+    }
+
+    /// Inserts `hash` into the filter.
+    ///
+    /// # Returns
+    /// `true` if `hash` was probably already present.
+    pub fn insert(&mut self, hash: u64) -> bool {
+        let mut present = true;
+        for bit in self.positions(hash) {
+            let word = &mut self.bits[bit >> 6];
+            present &= *word & (1 << (bit & 63)) != 0;
+            *word |= 1 << (bit & 63);
+        }
+        present
+    }
+
+    /// Forgets every inserted hash.
+    pub fn clear(&mut self) {
+        self.bits.fill(0);
+    }
+}
@@ -0,0 +1,15 @@
+//! Admission filtering in front of a full `Node`.
+//!
+//! Eviction policies only decide *which* entity leaves; an admission filter decides
+//! whether a new entity is worth that eviction at all. `TinyLfu` keeps a compact,
+//! periodically aged estimate of how often every hash was seen and only lets a
+//! candidate in when it has been seen more often than the victim.
+
+mod sketch;
+mod doorkeeper;
+mod tiny_lfu;
+mod test;
+
+pub use sketch::CountMinSketch;
+pub use doorkeeper::Doorkeeper;
+pub use tiny_lfu::TinyLfu;
@@ -0,0 +1,78 @@
+/// Number of rows of the sketch, each indexed by an independent hash.
+const DEPTH: usize = 4;
+
+/// Seeds mixed into the hash to derive the index of each row.
+const SEEDS: [u64; DEPTH] = [
+    0xC3A5_C85C_97CB_3127,
+    0xB492_B66F_BE98_F273,
+    0x9AE1_6A3B_2F90_404F,
+    0xCBF2_9CE4_8422_2325,
+];
+
+/// Mask keeping the low 3 bits of every 4-bit counter, used when halving.
+const HALVE_MASK: u64 = 0x7777_7777_7777_7777;
+
+/// A count-min sketch of 4-bit saturating counters.
+///
+/// Each `u64` word packs 16 counters and every row holds `width` counters.
+/// The estimate for a hash is the minimum of its counters across all rows, so it
+/// may overcount because of collisions but never undercounts.
+#[derive(Debug)]
+pub struct CountMinSketch {
+    /// `DEPTH` rows of `width / 16` words each, stored back to back.
+    table: Box<[u64]>,
+
+    /// `width - 1`, `width` being a power of two.
+    mask: usize,
+}
+
+impl CountMinSketch {
+    /// Creates a sketch with at least `width` counters per row.
+    ///
+    /// # Arguments
+    /// * `width` - Counters per row, rounded up to a power of two of at least 16.
+    pub fn new(width: usize) -> Self {
+        let width = width.max(16).next_power_of_two();
+        CountMinSketch {
+            table: vec![0; DEPTH * width / 16].into_boxed_slice(),
+            mask: width - 1,
+        }
+    }
+
+    /// Returns the word and bit shift of the counter of `hash` in `row`.
+    #[inline(always)]
+    fn position(&self, hash: u64, row: usize) -> (usize, u32) {
+        let mixed = (hash ^ SEEDS[row]).wrapping_mul(0x9E37_79B9_7F4A_7C15);
+        let counter = (mixed ^ (mixed >> 32)) as usize & self.mask;
+        let words_per_row = (self.mask + 1) / 16;
+        (row * words_per_row + counter / 16, (counter % 16) as u32 * 4)
+    }
+
+    /// Increments every counter of `hash` that is not yet saturated.
+    pub fn increment(&mut self, hash: u64) {
+        for row in 0..DEPTH {
+            let (word, shift) = self.position(hash, row);
+            if (self.table[word] >> shift) & 0xF != 0xF {
+                self.table[word] += 1 << shift;
+            }
+        }
+    }
+
+    /// Returns the estimated number of times `hash` was counted, at most 15.
+    pub fn estimate(&self, hash: u64) -> u8 {
+        (0..DEPTH)
+            .map(|row| {
+                let (word, shift) = self.position(hash, row);
+                ((self.table[word] >> shift) & 0xF) as u8
+            })
+            .min()
+            .unwrap_or(0)
+    }
+
+    /// Halves every counter so that old popularity fades away.
+    pub fn halve(&mut self) {
+        for word in self.table.iter_mut() {
+            *word = (*word >> 1) & HALVE_MASK;
+        }
+    }
+}
@@ -0,0 +1,92 @@
+use super::{CountMinSketch, Doorkeeper, TinyLfu};
+use super::super::array::ARR_SIZE;
+use super::super::node::Node;
+use super::super::node_impl::NodeImpl;
+
+#[test]
+pub fn admission1_sketch_and_doorkeeper(){
+    let mut sketch = CountMinSketch::new(1024);
+    for _ in 0..20{
+        sketch.increment(42);
+    }
+    sketch.increment(7);
+    assert_eq!(sketch.estimate(42), 15);
+    assert!(sketch.estimate(7) >= 1);
+    sketch.halve();
+    assert_eq!(sketch.estimate(42), 7);
+
+    let mut doorkeeper = Doorkeeper::new(1024);
+    assert!(!doorkeeper.insert(42));
+    assert!(doorkeeper.insert(42));
+    assert!(doorkeeper.contains(42));
+    doorkeeper.clear();
+    assert!(!doorkeeper.contains(42));
+}
+
+#[test]
+pub fn admission2_tiny_lfu_prefers_frequent(){
+    let mut filter = TinyLfu::with_capacity(64);
+    for _ in 0..5{
+        filter.record(1);
+    }
+    filter.record(2);
+    filter.track(0, 1);
+    filter.track(1, 2);
+    assert!(!filter.admit(2, 0));
+    assert!(filter.admit(1, 1));
+    assert!(!filter.admit(3, 1));
+}
+
+#[test]
+pub fn admission3_node_survives_scan(){
+    let mut node = Node::<u64,u64>::new().with_admission(TinyLfu::new());
+    for key in 0..ARR_SIZE as u64{
+        node.insert(key, key, key);
+        node.get(key, &key);
+    }
+    // A scan of keys seen only once must not flush the resident ones.
+    for key in 0..10_000u64{
+        node.insert(1_000_000 + key, 1_000_000 + key, key);
+    }
+    assert_eq!(node.len(), ARR_SIZE as usize);
+    // Keys that keep coming back are eventually admitted.
+    for _ in 0..4{
+        node.insert(2_000_000, 2_000_000, 1);
+    }
+    assert_eq!(node.get(2_000_000, &2_000_000), Some(&1));
+
+    // The sketch is approximate, yet nearly every resident key must survive.
+    let survivors = (0..ARR_SIZE as u64).filter(|key| node.get(*key, key) == Some(key)).count();
+    assert!(survivors > ARR_SIZE as usize * 99 / 100);
+}
+
+#[test]
+pub fn admission4_rejection_is_reported(){
+    use std::sync::Arc;
+    use super::super::weigher::Budget;
+
+    let mut node = Node::<u64,u64>::new().with_admission(TinyLfu::new());
+    for key in 0..ARR_SIZE as u64{
+        node.insert(key, key, key);
+        node.get(key, &key);
+    }
+    // A key seen once is turned away and handed back.
+    assert_eq!(node.try_insert(1_000_000, 1_000_000, 7, None), Err((1_000_000, 7)));
+    assert_eq!(node.get(1_000_000, &1_000_000), None);
+    assert_eq!(node.try_insert(0, 0, 1, None), Ok(Some(0)));
+
+    // A resident entry growing past the budget evicts others, however popular.
+    let weigher = |_: &u64, value: &Vec<u8>| value.len() as u32;
+    let budget = Arc::new(Budget::new(300));
+    let mut node = Node::<u64,Vec<u8>>::new().with_admission(TinyLfu::new()).with_weigher(weigher, budget.clone());
+    for key in 0..3u64{
+        node.insert(key, key, vec![0; 100]);
+    }
+    for _ in 0..10{
+        node.get(0, &0);
+        node.get(1, &1);
+    }
+    assert_eq!(node.and_modify(2, &2, |value| value.resize(200, 0)).map(Vec::len), Some(200));
+    assert_eq!((node.len(), budget.used()), (2, 300));
+    assert_eq!(node.get(0, &0), None);
+}
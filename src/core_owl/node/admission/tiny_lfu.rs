@@ -0,0 +1,90 @@
+use super::{CountMinSketch, Doorkeeper};
+use super::super::array::ARR_SIZE;
+
+/// TinyLFU admission filter.
+///
+/// There is no window cache in front of it: every newcomer to a full node
+/// competes with the victim right away.
+///
+/// Every access of a hash is recorded: its first sighting lands in the
+/// `Doorkeeper`, later ones in the `CountMinSketch`. After `sample_size` recorded
+/// accesses all counters are halved and the doorkeeper is cleared, so the
+/// estimates follow the workload as it shifts.
+///
+/// A candidate is admitted into a full node only if its estimated frequency is
+/// strictly higher than that of the victim picked by the eviction policy.
+#[derive(Debug)]
+pub struct TinyLfu {
+    /// Frequency estimates of repeatedly seen hashes.
+    sketch: CountMinSketch,
+
+    /// Hashes seen once since the last aging.
+    doorkeeper: Doorkeeper,
+
+    /// Hash of the entity stored in every slot, so victims can be estimated.
+    hashes: Box<[u64]>,
+
+    /// Accesses recorded since the last aging.
+    additions: usize,
+
+    /// Accesses between two agings.
+    sample_size: usize,
+}
+
+impl TinyLfu {
+    /// Creates a filter sized for a node of `ARR_SIZE` slots.
+    pub fn new() -> Self {
+        Self::with_capacity(ARR_SIZE as usize)
+    }
+
+    /// Creates a filter sized for `capacity` resident entities.
+    ///
+    /// # Arguments
+    /// * `capacity` - Expected number of resident entities, drives the sketch
+    ///   width, the doorkeeper size and the aging period (`10 * capacity`).
+    pub fn with_capacity(capacity: usize) -> Self {
+        let capacity = capacity.max(1);
+        TinyLfu {
+            sketch: CountMinSketch::new(capacity),
+            doorkeeper: Doorkeeper::new(capacity * 4),
+            hashes: vec![0; ARR_SIZE as usize].into_boxed_slice(),
+            additions: 0,
+            sample_size: capacity * 10,
+        }
+    }
+
+    /// Records one access of `hash`.
+    pub fn record(&mut self, hash: u64) {
+        if self.doorkeeper.insert(hash) {
+            self.sketch.increment(hash);
+        }
+        self.additions += 1;
+        if self.additions >= self.sample_size {
+            self.sketch.halve();
+            self.doorkeeper.clear();
+            self.additions = 0;
+        }
+    }
+
+    /// Returns the estimated access frequency of `hash`.
+    pub fn frequency(&self, hash: u64) -> u8 {
+        self.sketch.estimate(hash) + self.doorkeeper.contains(hash) as u8
+    }
+
+    /// Remembers that slot `idx` now stores an entity of hash `hash`.
+    #[inline(always)]
+    pub fn track(&mut self, idx: u16, hash: u64) {
+        self.hashes[idx as usize] = hash;
+    }
+
+    /// Decides whether a candidate of hash `candidate` may replace the entity in slot `victim`.
+    pub fn admit(&self, candidate: u64, victim: u16) -> bool {
+        self.frequency(candidate) > self.frequency(self.hashes[victim as usize])
+    }
+}
+
+impl Default for TinyLfu {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -1,6 +1,6 @@
 use std::{
     alloc::{alloc, dealloc, Layout}, // For dynamic memory allocation and deallocation.
-    mem::{forget, ManuallyDrop},     // `forget` prevents dropping of ownership, `ManuallyDrop` for manual drop control.
+    mem::MaybeUninit,                // Slots that may hold nothing.
     ptr::NonNull                     // Non-nullable pointer type.
 };
 
@@ -9,15 +9,26 @@ use std::simd::Simd; // SIMD operations for performance optimization.
 use super::ARR_SIZE; // Importing a constant defining the fixed array size.
 
 /// A structure representing an unsafe dynamically allocated array.
+///
+/// Every slot is a `MaybeUninit<T>`: slots start uninitialised, `set` fills one
+/// and `take` or `drop_idx` empties it again. The array does not know which
+/// slots are initialised, its owner does, so dropping the array drops none of
+/// its elements: the owner must empty every initialised slot first.
 /// 
 /// # Safety
 /// - This structure utilizes raw pointers and manual memory management.
 /// - Users must ensure safety while using methods to prevent undefined behavior.
+/// - Elements may only be read from initialised slots.
 pub struct UnsafeArray<T> {
     /// A non-nullable pointer to the start of the allocated memory block.
-    ptr: NonNull<T>,
+    ptr: NonNull<MaybeUninit<T>>,
 }
 
+// The array owns its elements like a `Box<[T]>` would, so it is sent and shared
+// between threads whenever they can be.
+unsafe impl<T: Send> Send for UnsafeArray<T> {}
+unsafe impl<T: Sync> Sync for UnsafeArray<T> {}
+
 impl<T> UnsafeArray<T> {
     /// Creates a new `UnsafeArray` instance with a predefined size.
     /// 
@@ -29,31 +40,16 @@ impl<T> UnsafeArray<T> {
     #[inline]
     pub fn new(size: usize) -> Self {
         assert_eq!(size, ARR_SIZE as usize); // Ensure the requested size matches the fixed size.
-        let layout = Layout::array::<T>(size).unwrap(); // Create a memory layout for the array.
+        let layout = Layout::array::<MaybeUninit<T>>(size).unwrap(); // Create a memory layout for the array.
         let ptr = unsafe {
             // Allocate memory and create a non-null pointer.
-            NonNull::new(alloc(layout) as *mut T).unwrap()
+            NonNull::new(alloc(layout) as *mut MaybeUninit<T>).unwrap()
         };
         UnsafeArray { ptr }
     }
 }
 
 impl<T> UnsafeArray<T> {
-    /// Provides manual ownership of the element at the specified index as a `ManuallyDrop`.
-    /// 
-    /// # Arguments
-    /// * `idx` - The index of the element.
-    /// 
-    /// # Returns
-    /// A `ManuallyDrop<T>` instance for the specified element.
-    /// 
-    /// # Safety
-    /// Caller must ensure the index is within bounds to avoid undefined behavior.
-    #[inline]
-    pub fn as_manual_cp(&self, idx: usize) -> ManuallyDrop<T> {
-        unsafe { ((self.ptr.add(idx)).as_ptr() as *mut ManuallyDrop<T>).read() }
-    }
-
     /// Provides a mutable reference to the element at the specified index.
     /// 
     /// # Arguments
@@ -63,10 +59,11 @@ impl<T> UnsafeArray<T> {
     /// A mutable reference to the element.
     /// 
     /// # Safety
-    /// Caller must ensure the index is within bounds to avoid undefined behavior.
+    /// Caller must ensure the index is within bounds and the slot initialised.
     #[inline]
+    #[allow(clippy::mut_from_ref)]
     pub fn as_mut(&self, idx: usize) -> &mut T {
-        unsafe { self.ptr.add(idx).as_mut() }
+        unsafe { self.ptr.add(idx).as_mut().assume_init_mut() }
     }
 
     /// Provides an immutable reference to the element at the specified index.
@@ -78,10 +75,10 @@ impl<T> UnsafeArray<T> {
     /// An immutable reference to the element.
     /// 
     /// # Safety
-    /// Caller must ensure the index is within bounds to avoid undefined behavior.
+    /// Caller must ensure the index is within bounds and the slot initialised.
     #[inline]
     pub fn as_ref(&self, idx: usize) -> &T {
-        unsafe { self.ptr.add(idx).as_ref() }
+        unsafe { self.ptr.add(idx).as_ref().assume_init_ref() }
     }
 }
 
@@ -94,25 +91,61 @@ impl<T> UnsafeArray<T> {
     /// 
     /// # Safety
     /// Caller must ensure the index is within bounds to avoid undefined behavior.
+    /// An element already in the slot is overwritten without being dropped.
     #[inline(always)]
     pub fn set(&mut self, idx: usize, data: T) {
         unsafe {
-            self.ptr.add(idx).write(data); // Write data to the specified index.
+            self.ptr.add(idx).as_mut().write(data); // Write data to the specified index.
         }
     }
 
-    pub fn as_ptr(&mut self)->*mut T{
-        self.ptr.as_ptr()
+    /// Moves the element out of the slot at the specified index, leaving the
+    /// slot uninitialised.
+    ///
+    /// # Arguments
+    /// * `idx` - The index of the element.
+    ///
+    /// # Returns
+    /// The element.
+    ///
+    /// # Safety
+    /// Caller must ensure the index is within bounds and the slot initialised,
+    /// and must not read the slot again before setting it.
+    #[inline(always)]
+    pub fn take(&self, idx: usize) -> T {
+        unsafe { self.ptr.add(idx).as_ref().assume_init_read() }
+    }
+
+    /// Drops the element at the specified index in place, leaving the slot
+    /// uninitialised.
+    ///
+    /// # Arguments
+    /// * `idx` - The index of the element.
+    ///
+    /// # Safety
+    /// Caller must ensure the index is within bounds and the slot initialised,
+    /// and must not read the slot again before setting it.
+    #[inline(always)]
+    pub fn drop_idx(&mut self, idx: usize) {
+        unsafe { self.ptr.add(idx).as_mut().assume_init_drop() }
+    }
+
+    /// Returns a raw pointer to the first slot, initialised or not, without
+    /// creating a reference to any of them.
+    #[inline(always)]
+    pub fn as_ptr(&self)->*mut T{
+        self.ptr.as_ptr().cast()
     }
 }
 
 impl<T> Drop for UnsafeArray<T> {
-    /// Drops the `UnsafeArray` and deallocates its memory.
+    /// Drops the `UnsafeArray` and deallocates its memory, without dropping any
+    /// element left in it.
     /// 
     /// # Safety
     /// Ensures that the memory layout matches the allocation to avoid undefined behavior.
     fn drop(&mut self) {
-        let layout = Layout::array::<T>(ARR_SIZE as usize).unwrap(); // Get the memory layout.
+        let layout = Layout::array::<MaybeUninit<T>>(ARR_SIZE as usize).unwrap(); // Get the memory layout.
         unsafe {
             dealloc(self.ptr.as_ptr() as *mut u8, layout); // Deallocate memory.
         }
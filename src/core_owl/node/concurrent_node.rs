@@ -0,0 +1,196 @@
+use std::borrow::Borrow;
+use std::hash::Hash;
+use std::ops::Deref;
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::Duration;
+
+use super::epoch::Pin;
+use super::handle::Handle;
+use super::hash_line::HashLine;
+use super::node::Node;
+use super::node_impl::NodeImpl;
+use super::policy::{EvictionPolicy, Lru};
+use super::read_line::{ReadLine, Record};
+
+/// A `Node` whose reads take no lock at all.
+///
+/// Writers are serialized by a `Mutex` around the node, which mirrors its chains
+/// into a `ReadLine`. `get` only pins an epoch and walks that line, validating
+/// every slot against its version counter and every link against the generation
+/// of the slot it leads to, so any number of readers run alongside each other
+/// and alongside the writer.
+///
+/// Values are kept in `Arc`s, which the published records share with the node:
+/// an evicted or overwritten value is only dropped once its record is collected,
+/// which waits for every `ValueRef` that could still point to it.
+///
+/// Lock-free hits reach the eviction policy when the next write replays them,
+/// and may be lost if many land between two writes. Expired entries stay
+/// readable until a write purges them.
+///
+/// # Generics
+/// - `K`: Key type, must support hashing and equality.
+/// - `V`: Value type.
+/// - `P`: Eviction policy, `Lru` by default.
+pub struct ConcurrentNode<K, V, P = Lru>
+where
+    K: Hash + Eq,
+    P: EvictionPolicy<K, Arc<V>>,
+{
+    /// The node, locked by writers only.
+    node: Mutex<Node<K, Arc<V>, P>>,
+
+    /// The chains of the node, shared with the readers.
+    reads: Arc<ReadLine<K, Arc<V>>>,
+}
+
+/// A reference to a value of a `ConcurrentNode`, keeping it alive.
+///
+/// # Lifetimes
+/// - `'a`: Lifetime tied to the node the value was read from.
+pub struct ValueRef<'a, K, V> {
+    record: &'a Record<K, Arc<V>>, // Record holding the value.
+    handle: Handle,                // Slot of the record when it was read.
+    _pin: Pin<'a, Record<K, Arc<V>>>, // Keeps the record from being collected.
+}
+
+impl<K, V, P> ConcurrentNode<K, V, P>
+where
+    K: Hash + Eq + Clone,
+    P: EvictionPolicy<K, Arc<V>> + Default,
+{
+    /// Creates a new, empty `ConcurrentNode` with the default instance of its policy.
+    pub fn new() -> Self {
+        Self::with_node(Node::new())
+    }
+}
+
+impl<K, V, P> ConcurrentNode<K, V, P>
+where
+    K: Hash + Eq + Clone,
+    P: EvictionPolicy<K, Arc<V>>,
+{
+    /// Wraps `node`, keeping its policy, admission filter, weigher or budget.
+    ///
+    /// # Panics
+    /// If the node already holds entries.
+    pub fn with_node(mut node: Node<K, Arc<V>, P>) -> Self {
+        let reads = node.share_reads();
+        ConcurrentNode {
+            node: Mutex::new(node),
+            reads,
+        }
+    }
+
+    /// Locks the node for a write, replaying the lock-free hits first.
+    fn write(&self) -> MutexGuard<'_, Node<K, Arc<V>, P>> {
+        let mut node = self.node.lock().unwrap();
+        node.replay_hits();
+        node
+    }
+
+    /// Drops the values evicted or overwritten by the previous writes that no
+    /// reader can reference anymore.
+    fn collect(&self) {
+        self.reads.collect();
+    }
+
+    /// Retrieves the value of `key` without taking any lock.
+    ///
+    /// # Returns
+    /// A reference to the value, which stays valid even if the entry is evicted
+    /// or overwritten meanwhile.
+    pub fn get<Q>(&self, hash_val: u64, key: &Q) -> Option<ValueRef<'_, K, V>>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        let pin = self.reads.pin();
+        let (record, handle) = self.reads.find(&pin, HashLine::hash_idx(hash_val), key)?;
+        self.reads.hit(handle);
+        // The record outlives the pin's borrow of it as long as the pin itself lives.
+        let record = unsafe { &*(record as *const Record<K, Arc<V>>) };
+        Some(ValueRef { record, handle, _pin: pin })
+    }
+
+    /// Inserts a key-value pair, evicting an entry if the node is full.
+    ///
+    /// # Returns
+    /// The previous value if the key was already present.
+    pub fn insert(&self, hash_val: u64, key: K, value: V) -> Option<Arc<V>> {
+        let previous = self.write().insert(hash_val, key, Arc::new(value));
+        self.collect();
+        previous
+    }
+
+    /// Inserts a key-value pair that expires `ttl` from now.
+    ///
+    /// # Returns
+    /// The previous value if the key was present and not expired.
+    pub fn insert_with_ttl(&self, hash_val: u64, key: K, value: V, ttl: Duration) -> Option<Arc<V>> {
+        let previous = self.write().insert_with_ttl(hash_val, key, Arc::new(value), ttl);
+        self.collect();
+        previous
+    }
+
+    /// Removes `key` from the node.
+    ///
+    /// # Returns
+    /// The removed value, if the key was present.
+    pub fn remove<Q>(&self, hash_val: u64, key: &Q) -> Option<Arc<V>>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        let removed = self.write().delete(hash_val, key).map(|(_, value)| value);
+        self.collect();
+        removed
+    }
+
+    /// Returns the number of entries stored in the node.
+    pub fn len(&self) -> usize {
+        self.node.lock().unwrap().len()
+    }
+
+    /// Returns `true` if the node holds no entries.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns `true` if the entry `handle` was read from is still in the node.
+    pub fn is_live(&self, handle: Handle) -> bool {
+        self.node.lock().unwrap().is_live(handle)
+    }
+
+    /// Returns the number of evicted or overwritten values still waiting for
+    /// readers to leave before being dropped.
+    pub fn pending_drops(&self) -> usize {
+        self.reads.pending()
+    }
+}
+
+impl<K, V, P> Default for ConcurrentNode<K, V, P>
+where
+    K: Hash + Eq + Clone,
+    P: EvictionPolicy<K, Arc<V>> + Default,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K, V> ValueRef<'_, K, V> {
+    /// Returns the handle of the slot the value was read from, which goes stale
+    /// once the entry leaves the node.
+    pub fn handle(&self) -> Handle {
+        self.handle
+    }
+}
+
+impl<K, V> Deref for ValueRef<'_, K, V> {
+    type Target = V;
+
+    fn deref(&self) -> &Self::Target {
+        &self.record.value
+    }
+}
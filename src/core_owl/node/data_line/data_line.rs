@@ -1,6 +1,6 @@
 
 use std::hash::Hash;
-use crate::core_owl::node::data_line::entity::Link;
+use crate::core_owl::node::NULL_IDX;
 
 use super::entity::Entity;
 use super::super::array::{
@@ -8,17 +8,31 @@ use super::super::array::{
     unsafe_array::UnsafeArray
 };
 pub struct DataLine<K,V>
-where K:Hash+Ord+PartialOrd+Eq+PartialEq{
+where K:Hash+Eq{
     data:UnsafeArray<Entity<K,V>>
 }
 
 impl<K,V> DataLine<K,V>
-where K:Hash+Ord+PartialOrd+Eq+PartialEq{
+where K:Hash+Eq{
     pub fn new()->Self{
         DataLine{
             data:UnsafeArray::new(ARR_SIZE as usize)
         }
     }
+
+    /// Drops the entity stored at `idx` in place, leaving the slot uninitialised.
+    /// The line does not track its occupied slots, so its owner must call this
+    /// for each of them before the line is dropped.
+    pub fn drop_idx(&mut self,idx:u16){
+        self.data.drop_idx(idx as usize);
+    }
+}
+
+impl<K,V> Default for DataLine<K,V>
+where K:Hash+Eq{
+    fn default()->Self{
+        Self::new()
+    }
 }
 
 use super::data_line_impl::{ChainLinker, DataLineImpl};
@@ -26,9 +40,10 @@ use super::entity_iter::EntityIter;
 use std::ptr::NonNull;
 
 impl<K,V> DataLineImpl<K,V> for DataLine<K,V>
-where K:Hash+Ord+PartialOrd+Eq+PartialEq+Default,V:Default{
+where K:Hash+Eq{
     fn as_ptr(&self)->*mut Entity<K,V> {
-        self.data.as_mut(0) 
+        // Slot 0 may be uninitialised, so no reference to it is created.
+        self.data.as_ptr()
     }
     fn get_ref(&self,idx:u16)->&Entity<K,V> {
         self.data.as_ref(idx as usize)
@@ -36,9 +51,12 @@ where K:Hash+Ord+PartialOrd+Eq+PartialEq+Default,V:Default{
     fn get_mut(&mut self,idx:u16)->&mut Entity<K,V> {
         self.data.as_mut(idx as usize)
     }
+    fn set(&mut self,idx:u16,entity:Entity<K,V>) {
+        self.data.set(idx as usize, entity);
+    }
     fn entity_iter(&self,idx:u16,link:bool)->EntityIter<K,V> {
         EntityIter::new(
-            NonNull::new(self.as_ptr()).unwrap(), 
+            NonNull::new(self.as_ptr()).unwrap(),
             idx,
             link
         ).unwrap()
@@ -46,52 +64,52 @@ where K:Hash+Ord+PartialOrd+Eq+PartialEq+Default,V:Default{
     fn set_val(&mut self,val:V,idx:u16) {
         self.data.as_mut(idx as usize).lock().set_val(val);
     }
-    fn take(&self,key:&K,idx:u16)->Option<Entity<K,V>> {
-        for entity in &mut self.entity_iter(idx, false){
-            if entity.0.is_same_key(key){
-                let r= unsafe {
-                    todo!("need to update link connections and chain connections");
-                    self.link_cl(self.as_ptr(), entity.0, true);
-                    let res = self.as_ptr().add(entity.1 as usize).replace(
-                        Entity::new(
-                        K::default(), V::default(), Link::default(), Link::default()
-                    ));
-                    res
-                };
-                return Some(r);
-            }
+    fn take(&self,key:&K,idx:u16)->Option<(Entity<K,V>,u16)> {
+        let idx = (&mut self.entity_iter(idx, false))
+            .find(|entity| entity.0.is_same_key(key))?
+            .1;
+        Some((self.take_idx(idx),idx))
+    }
+    fn take_idx(&self,idx:u16)->Entity<K,V> {
+        // Detach from the collision chain first, then from the link list.
+        unsafe {
+            self.link_cl(self.as_ptr(), self.lock_entity(idx), false);
+            self.link_cl(self.as_ptr(), self.lock_entity(idx), true);
         }
-        None
+        // The slot is left uninitialised until the next `set`.
+        self.data.take(idx as usize)
     }
     fn lock_entity(&self,idx:u16) ->super::entity::EntityGuard<'_,K,V> {
-        self.data.as_mut(idx as usize).lock()
+        self.data.as_ref(idx as usize).lock()
     }
 }
 
 
 impl<K,V> ChainLinker for DataLine<K,V>
-where K:Hash+Ord+PartialOrd+Eq+PartialEq+Default,V:Default{
+where K:Hash+Eq{
 
-    fn link_cl<'a,Key,Val>(&self, base_ptr:*mut Entity<Key,Val>, val:super::entity::EntityGuard<'a,Key,Val>, link:bool)
-        where 
-            Key:Hash+Ord+PartialOrd+Eq+PartialEq {
-                
-                let prev = unsafe {
-                    base_ptr.add(
-                        match link{
-                            true=>val.link.prev as usize,
-                            false=>val.chain.prev as usize
-                        }
-                    ).as_mut().unwrap().lock()
-                };
-                let next = unsafe {
-                    base_ptr.add(
-                        match link{
-                            true=>val.link.next as usize,
-                            false=>val.chain.next as usize
-                        }
-                    ).as_mut().unwrap().lock()
+    unsafe fn link_cl<'a,Key,Val>(&self, base_ptr:*mut Entity<Key,Val>, val:super::entity::EntityGuard<'a,Key,Val>, link:bool)
+        where
+            Key:Hash+Eq {
+                let (prev_idx, next_idx) = match link{
+                    true=>(val.link.prev, val.link.next),
+                    false=>(val.chain.prev, val.chain.next)
                 };
-                todo!()
+
+                // The links are guarded by the node, not by the entity locks.
+                if prev_idx != NULL_IDX {
+                    let prev = base_ptr.add(prev_idx as usize).as_mut().unwrap();
+                    match link{
+                        true=>prev.link.next = next_idx,
+                        false=>prev.chain.next = next_idx
+                    }
+                }
+                if next_idx != NULL_IDX {
+                    let next = base_ptr.add(next_idx as usize).as_mut().unwrap();
+                    match link{
+                        true=>next.link.prev = prev_idx,
+                        false=>next.chain.prev = prev_idx
+                    }
+                }
     }
-}
\ No newline at end of file
+}
@@ -3,24 +3,41 @@ use std::hash::Hash;
 use super::entity::{Entity, EntityGuard};
 use super::entity_iter::EntityIter;
 
-pub trait DataLineImpl<K,V> 
-where K:Hash+Ord+PartialOrd+Eq+PartialEq{
+pub trait DataLineImpl<K,V>
+where K:Hash+Eq{
     fn get_ref(&self,idx:u16)->&Entity<K,V>;
 
     fn get_mut(&mut self,idx:u16)->&mut Entity<K,V>;
 
-    fn take(&self,key:&K,idx:u16)->Option<Entity<K,V>>;
+    /// Writes `entity` into the slot at `idx` without dropping the previous occupant.
+    fn set(&mut self,idx:u16,entity:Entity<K,V>);
+
+    /// Removes the entity matching `key` from the chain starting at `idx`,
+    /// unlinking it from both its hash chain and its link list.
+    /// Returns the removed entity together with the slot it occupied.
+    fn take(&self,key:&K,idx:u16)->Option<(Entity<K,V>,u16)>;
+
+    /// Removes the entity stored at `idx`, unlinking it from both its hash chain
+    /// and its link list. The returned entity keeps its old links, and the slot
+    /// is left uninitialised until the next `set`.
+    fn take_idx(&self,idx:u16)->Entity<K,V>;
 
     fn set_val(&mut self,val:V,idx:u16);
 
     fn as_ptr(&self)->*mut Entity<K,V>;
-    
+
     fn lock_entity(&self,idx:u16) ->EntityGuard<'_,K,V>;
 
     fn entity_iter(&self,idx:u16,link:bool)->EntityIter<K,V>;
 }
 
 pub trait ChainLinker{
-    fn link_cl<'a,K,V>(&self, base_ptr:*mut Entity<K,V>, val:EntityGuard<'a,K,V>, link:bool)
-    where K:Hash+Ord+PartialOrd+Eq+PartialEq;
-}
\ No newline at end of file
+    /// Connects the neighbours of `val` to each other, removing `val` from the
+    /// link list (`link == true`) or from its hash chain (`link == false`).
+    /// The links stored in `val` itself are left untouched.
+    ///
+    /// # Safety
+    /// `base_ptr` must point to the start of the `DataLine` that owns `val`.
+    unsafe fn link_cl<'a,K,V>(&self, base_ptr:*mut Entity<K,V>, val:EntityGuard<'a,K,V>, link:bool)
+    where K:Hash+Eq;
+}
@@ -1,16 +1,18 @@
-use std::hint::spin_loop; // CPU hint to reduce power consumption during spin-wait loops.
-use std::mem::ManuallyDrop; // Prevents automatic cleanup for manual memory management.
+use std::borrow::Borrow; // Lets keys be compared with borrowed forms of themselves.
+use std::cell::UnsafeCell; // Lets the value be written through a shared reference under the lock.
 use std::hash::Hash; // Enables hashing capabilities for keys.
-use std::ops::{Deref, DerefMut}; // Traits for dereferencing and mutable dereferencing.
+use std::ops::Deref; // Lets guards be used as what they guard.
 use std::sync::atomic::{
-    AtomicBool, // Atomic boolean for thread-safe lock management.
-    Ordering::{Release, Acquire, AcqRel}, // Memory ordering for atomic operations.
+    AtomicBool, // Atomic flag marking visited entities.
+    Ordering::Relaxed, // Memory ordering for atomic operations.
 };
 
+use super::entity_lock::EntityLock; // Reader-writer lock packed in the entity header.
+
 use super::super::NULL_IDX; // Placeholder for null or sentinel value representation.
 
 /// Represents a doubly linked list node with references to previous and next elements.
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct Link {
     /// Index of the previous node.
     pub prev: u16,
@@ -32,36 +34,66 @@ impl Default for Link {
 
 /// Represents a key-value entity with locking and linking mechanisms.
 ///
+/// The value is guarded by an `EntityLock`: `read` lets threads sharing the entity
+/// read it concurrently, while `lock` gives a writer exclusive access to it. The
+/// links are only changed through a mutable reference to the entity.
+///
+/// With the `full-hash` feature, the entity also keeps the full hash of its key,
+/// so lookups compare hashes before keys and the key is never hashed again.
+///
 /// # Generics
-/// - `K`: Key type, must support hashing, ordering, and equality.
+/// - `K`: Key type, must support hashing and equality.
 /// - `V`: Value type.
 #[derive(Debug)]
 pub struct Entity<K, V>
 where
-    K: Hash + Ord + PartialOrd + Eq + PartialEq,
+    K: Hash + Eq,
 {
     key: K,              // The key of the entity.
-    val: V,              // The value associated with the key.
-    lock: AtomicBool,    // Atomic lock to ensure thread safety during access.
+    val: UnsafeCell<V>,  // The value associated with the key.
+    lock: EntityLock,    // Reader-writer lock guarding the value.
+    visited: AtomicBool, // Set on every hit, cleared by sweeping eviction policies.
     pub link: Link,      // Link for doubly linked list operations.
     pub chain: Link,     // Link for collision handling in hash chains.
+    pub hash_idx: u16,   // `HashLine` bucket whose chain holds this entity.
+    #[cfg(feature = "full-hash")]
+    hash: u64,           // Full hash of the key.
 }
 
-/// Provides a guard for safely accessing an `Entity` while holding its lock.
+/// Provides a guard for safely accessing an `Entity` while holding its lock for
+/// writing.
 ///
 /// # Lifetimes
 /// - `'a`: Lifetime tied to the referenced `Entity`.
 #[derive(Debug)]
 pub struct EntityGuard<'a, K, V>
 where
-    K: Hash + Ord + PartialOrd + Eq + PartialEq,
+    K: Hash + Eq,
+{
+    data: &'a Entity<K, V>, // Shared reference to the guarded entity.
+}
+
+/// Shared access to the value of an `Entity`, holding its lock for reading.
+#[derive(Debug)]
+pub struct EntityReadGuard<'a, K, V>
+where
+    K: Hash + Eq,
+{
+    data: &'a Entity<K, V>, // Shared reference to the guarded entity.
+}
+
+// The value is only reached through the lock, so entities can be shared between
+// threads as soon as their keys and values can.
+unsafe impl<K, V> Sync for Entity<K, V>
+where
+    K: Hash + Eq + Sync,
+    V: Send + Sync,
 {
-    data: &'a mut Entity<K, V>, // Mutable reference to the guarded entity.
 }
 
 impl<K, V> Entity<K, V>
 where
-    K: Hash + Ord + PartialOrd + Eq + PartialEq,
+    K: Hash + Eq,
 {
     /// Creates a new entity with the given key, value, and links.
     ///
@@ -73,93 +105,231 @@ where
     pub fn new(key: K, val: V, link: Link, chain: Link) -> Self {
         Entity {
             key,
-            val,
+            val: UnsafeCell::new(val),
             link,
             chain,
-            lock: AtomicBool::new(false), // Initializes the lock to false (unlocked).
+            hash_idx: NULL_IDX, // Not attached to any bucket yet.
+            lock: EntityLock::new(), // Starts unlocked.
+            visited: AtomicBool::new(false), // Not visited until the first hit.
+            #[cfg(feature = "full-hash")]
+            hash: 0, // Set once the entity is attached to a bucket.
+        }
+    }
+
+    /// Remembers the full hash of the key, if the `full-hash` feature is enabled.
+    #[inline(always)]
+    pub fn set_hash(&mut self, hash_val: u64) {
+        #[cfg(feature = "full-hash")]
+        {
+            self.hash = hash_val;
+        }
+        #[cfg(not(feature = "full-hash"))]
+        let _ = hash_val;
+    }
+
+    /// Returns the full hash of the key, calling `rehash` only if the entity
+    /// does not store it.
+    #[inline(always)]
+    pub fn hash_or(&self, rehash: impl FnOnce(&K) -> u64) -> u64 {
+        #[cfg(feature = "full-hash")]
+        {
+            let _ = rehash;
+            self.hash
+        }
+        #[cfg(not(feature = "full-hash"))]
+        rehash(&self.key)
+    }
+
+    /// Checks if the entity holds `key`, whose full hash is `hash_val`.
+    ///
+    /// With the `full-hash` feature the stored hashes are compared first, so
+    /// the keys are only compared when the hashes are equal.
+    ///
+    /// # Arguments
+    /// - `key`: The key to compare against, or any borrowed form of it.
+    ///
+    /// # Returns
+    /// `true` if the keys match, otherwise `false`.
+    #[inline(always)]
+    pub fn matches<Q>(&self, hash_val: u64, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: Eq + ?Sized,
+    {
+        #[cfg(feature = "full-hash")]
+        if self.hash != hash_val {
+            return false;
         }
+        #[cfg(not(feature = "full-hash"))]
+        let _ = hash_val;
+        self.key.borrow() == key
     }
 
     /// Checks if the entity's key matches the given key.
     ///
     /// # Arguments
-    /// - `key`: The key to compare against.
+    /// - `key`: The key to compare against, or any borrowed form of it.
     ///
     /// # Returns
     /// `true` if the keys match, otherwise `false`.
     #[inline(always)]
-    pub fn is_same_key(&self, key: &K) -> bool {
-        &self.key == key
+    pub fn is_same_key<Q>(&self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: Eq + ?Sized,
+    {
+        self.key.borrow() == key
     }
 
-    /// Locks the entity and returns a guard for safe access.
-    pub fn lock(&mut self) -> EntityGuard<'_, K, V> {
-        while self.lock.swap(true, AcqRel) {
-            spin_loop(); // Spin-wait until the lock is acquired.
+    /// Marks the entity as visited.
+    ///
+    /// Only the entity itself is written, and only if the flag is not already set,
+    /// so a hit never needs the lock nor touches neighbouring entities.
+    #[inline(always)]
+    pub fn mark_visited(&self) {
+        if !self.visited.load(Relaxed) {
+            self.visited.store(true, Relaxed);
         }
+    }
+
+    /// Clears the visited flag.
+    ///
+    /// # Returns
+    /// `true` if the entity had been visited since the flag was last cleared.
+    #[inline(always)]
+    pub fn clear_visited(&self) -> bool {
+        self.visited.load(Relaxed) && self.visited.swap(false, Relaxed)
+    }
+
+    /// Locks the value for writing, waiting for every other holder, and returns
+    /// a guard for safe access.
+    #[inline(always)]
+    pub fn lock(&self) -> EntityGuard<'_, K, V> {
+        self.lock.write();
         EntityGuard { data: self }
     }
 
-    /// Returns a reference to the value stored in the entity.
+    /// Locks the value for reading, waiting for a writer holding it, if any.
     #[inline(always)]
-    fn as_ref(&self) -> &V {
-        &self.val
+    pub fn read(&self) -> EntityReadGuard<'_, K, V> {
+        self.lock.read();
+        EntityReadGuard { data: self }
     }
 
-    /// Sets a new value for the entity.
-    ///
-    /// # Arguments
-    /// - `val`: The new value to set.
+    /// Returns a reference to the value, which the caller must have locked.
+    #[inline(always)]
+    unsafe fn as_ref(&self) -> &V {
+        &*self.val.get()
+    }
+
+    /// Returns a reference to the value without taking the lock, which the
+    /// mutable borrow makes unnecessary.
+    #[inline(always)]
+    pub fn value(&mut self) -> &V {
+        self.val.get_mut()
+    }
+
+    /// Returns references to the key and the value, like `key` and `value`.
     #[inline(always)]
-    fn set_val(&mut self, val: V) {
-        self.val = val;
+    pub fn key_value(&mut self) -> (&K, &V) {
+        (&self.key, self.val.get_mut())
+    }
+
+    /// Returns a reference to the key.
+    #[inline(always)]
+    pub fn key(&self) -> &K {
+        &self.key
+    }
+
+    /// Consumes the entity and returns its key and value.
+    #[inline(always)]
+    pub fn into_inner(self) -> (K, V) {
+        (self.key, self.val.into_inner())
+    }
+
+    /// Returns a mutable reference to the value, which the caller must have
+    /// locked for writing.
+    #[inline(always)]
+    #[allow(clippy::mut_from_ref)]
+    unsafe fn as_mut(&self) -> &mut V {
+        &mut *self.val.get()
     }
 }
 
 impl<'a, K, V> EntityGuard<'a, K, V>
 where
-    K: Hash + Ord + PartialOrd + Eq + PartialEq,
+    K: Hash + Eq,
 {
     /// Sets a new value for the guarded entity.
     ///
     /// # Arguments
     /// - `val`: The new value to set.
     pub fn set_val(&mut self, val: V) {
-        self.data.set_val(val);
+        *self.value_mut() = val;
+    }
+
+    /// Replaces the value of the guarded entity, returning the previous value.
+    ///
+    /// # Arguments
+    /// - `val`: The new value to set.
+    pub fn replace_val(&mut self, val: V) -> V {
+        std::mem::replace(self.value_mut(), val)
     }
 
     /// Returns a reference to the value of the guarded entity.
+    #[allow(clippy::should_implement_trait)]
     pub fn as_ref(&self) -> &V {
-        self.data.as_ref()
+        // The write lock excludes every writer but this guard.
+        unsafe { self.data.as_ref() }
+    }
+
+    /// Returns a mutable reference to the value of the guarded entity.
+    pub fn value_mut(&mut self) -> &mut V {
+        // The write lock excludes every other guard of this entity.
+        unsafe { self.data.as_mut() }
     }
 }
 
 impl<'a, K, V> Drop for EntityGuard<'a, K, V>
 where
-    K: Hash + Ord + PartialOrd + Eq + PartialEq,
+    K: Hash + Eq,
 {
     /// Releases the lock when the guard goes out of scope.
     fn drop(&mut self) {
-        self.data.lock.store(false, Release);
+        self.data.lock.write_unlock();
     }
 }
 
-impl<'a, K, V> Deref for EntityGuard<'a, K, V>
+impl<'a, K, V> Drop for EntityReadGuard<'a, K, V>
 where
-    K: Hash + Ord + PartialOrd + Eq + PartialEq,
+    K: Hash + Eq,
 {
-    type Target = Entity<K, V>;
+    /// Releases the read lock when the guard goes out of scope.
+    fn drop(&mut self) {
+        self.data.lock.read_unlock();
+    }
+}
+
+impl<'a, K, V> Deref for EntityReadGuard<'a, K, V>
+where
+    K: Hash + Eq,
+{
+    type Target = V;
 
     fn deref(&self) -> &Self::Target {
-        self.data
+        // The read lock excludes every writer.
+        unsafe { self.data.as_ref() }
     }
 }
 
-impl<'a, K, V> DerefMut for EntityGuard<'a, K, V>
+impl<'a, K, V> Deref for EntityGuard<'a, K, V>
 where
-    K: Hash + Ord + PartialOrd + Eq + PartialEq,
+    K: Hash + Eq,
 {
-    fn deref_mut(&mut self) -> &mut Self::Target {
+    type Target = Entity<K, V>;
+
+    fn deref(&self) -> &Self::Target {
         self.data
     }
 }
+
@@ -8,12 +8,12 @@ use super::super::super::node::NULL_IDX;
 /// - **Chain-based iteration**: Follows the `chain` pointers.
 ///
 /// # Type Parameters
-/// - `K`: The key type, requiring `Hash`, `Ord`, `PartialOrd`, `Eq`, and `PartialEq`.
+/// - `K`: The key type, requiring `Hash` and `Eq`.
 /// - `V`: The value type associated with the key.
 #[derive(Debug)]
 pub struct EntityIter<K, V>
 where
-    K: Hash + Ord + PartialOrd + Eq + PartialEq,
+    K: Hash + Eq,
 {
     /// Raw pointer to the base array of `Entity` objects.
     /// 
@@ -33,7 +33,7 @@ where
 
 impl<K, V> EntityIter<K, V>
 where
-    K: Hash + Ord + PartialOrd + Eq + PartialEq,
+    K: Hash + Eq,
 {
     /// Creates a new `EntityIter`.
     ///
@@ -64,7 +64,7 @@ where
 
 impl<'a, K, V> Iterator for &'a mut EntityIter<K, V>
 where
-    K: Hash + Ord + PartialOrd + Eq + PartialEq,
+    K: Hash + Eq,
 {
     /// The type of item returned by the iterator.
     /// Each item is a tuple of:
@@ -84,8 +84,8 @@ where
         }
 
         unsafe {
-            // Get a mutable reference to the current `Entity` and lock it.
-            let res = (self.ptr.add(self.next as usize).as_mut().unwrap().lock(), self.next);
+            // Get a reference to the current `Entity` and lock it.
+            let res = (self.ptr.add(self.next as usize).as_ref().unwrap().lock(), self.next);
 
             // Update `next` to point to the next `Entity` based on the iteration mode.
             self.next = if self.link {
@@ -0,0 +1,174 @@
+use std::hint::spin_loop; // CPU hint to reduce power consumption during spin-wait loops.
+use std::sync::atomic::{
+    AtomicU32, // Packed lock state: writer bit, parked bit and reader count.
+    Ordering::{Acquire, Relaxed, Release}, // Memory ordering for atomic operations.
+};
+use std::sync::{Condvar, Mutex, PoisonError};
+
+/// Set while a writer holds the lock.
+const WRITER: u32 = 1 << 31;
+
+/// Set while at least one thread sleeps waiting for the lock.
+const PARKED: u32 = 1 << 30;
+
+/// Mask of the number of readers holding the lock.
+const READERS: u32 = PARKED - 1;
+
+/// Rounds of exponential backoff spent spinning before parking, `63` spins in total.
+const SPIN_ROUNDS: u32 = 6;
+
+/// Number of parking buckets shared by every `EntityLock`.
+const BUCKETS: usize = 64;
+
+/// Parked threads sleep on the bucket of the lock they wait for. Locks sharing a
+/// bucket only cause spurious wake-ups, so a small table is enough.
+static PARKING_LOT: [(Mutex<()>, Condvar); BUCKETS] = [const { (Mutex::new(()), Condvar::new()) }; BUCKETS];
+
+/// A reader-writer lock packed into a single `u32` of the entity header.
+///
+/// Any number of readers, or a single writer, hold the lock at a time. A thread
+/// that cannot take the lock spins with exponential backoff for a bounded number
+/// of rounds, then sets the `PARKED` bit and sleeps in the global parking lot
+/// until the holder releasing the lock wakes it up. An uncontended lock or
+/// unlock is a single atomic operation and never touches the parking lot.
+#[derive(Debug, Default)]
+pub struct EntityLock {
+    state: AtomicU32, // `WRITER | PARKED | readers`.
+}
+
+impl EntityLock {
+    /// Creates an unlocked lock.
+    pub const fn new() -> Self {
+        EntityLock {
+            state: AtomicU32::new(0),
+        }
+    }
+
+    /// Takes the lock for reading, if no writer holds it.
+    ///
+    /// # Returns
+    /// `true` if the lock was taken.
+    #[inline(always)]
+    pub fn try_read(&self) -> bool {
+        let state = self.state.load(Relaxed);
+        state & WRITER == 0
+            && state & READERS != READERS
+            && self.state.compare_exchange_weak(state, state + 1, Acquire, Relaxed).is_ok()
+    }
+
+    /// Takes the lock for writing, if nobody holds it.
+    ///
+    /// # Returns
+    /// `true` if the lock was taken.
+    #[inline(always)]
+    pub fn try_write(&self) -> bool {
+        let state = self.state.load(Relaxed);
+        state & !PARKED == 0 && self.state.compare_exchange_weak(state, state | WRITER, Acquire, Relaxed).is_ok()
+    }
+
+    /// Takes the lock for reading, waiting for the writer holding it, if any.
+    #[inline(always)]
+    pub fn read(&self) {
+        if !self.try_read() {
+            self.acquire(Self::try_read, WRITER);
+        }
+    }
+
+    /// Takes the lock for writing, waiting for every other holder.
+    #[inline(always)]
+    pub fn write(&self) {
+        if !self.try_write() {
+            self.acquire(Self::try_write, WRITER | READERS);
+        }
+    }
+
+    /// Releases a read lock, waking the parked threads if it was the last reader.
+    #[inline(always)]
+    pub fn read_unlock(&self) {
+        if self.state.fetch_sub(1, Release) == PARKED | 1 {
+            self.unpark();
+        }
+    }
+
+    /// Releases the write lock, waking the parked threads if any.
+    #[inline(always)]
+    pub fn write_unlock(&self) {
+        if self.state.fetch_and(!WRITER, Release) & PARKED != 0 {
+            self.unpark();
+        }
+    }
+
+    /// Returns `true` if a writer holds the lock.
+    #[inline(always)]
+    pub fn is_write_locked(&self) -> bool {
+        self.state.load(Relaxed) & WRITER != 0
+    }
+
+    /// Returns the number of readers holding the lock.
+    #[inline(always)]
+    pub fn readers(&self) -> u32 {
+        self.state.load(Relaxed) & READERS
+    }
+
+    /// Slow path of `read` and `write`: spins, then parks until `try_lock` succeeds.
+    ///
+    /// # Arguments
+    /// * `try_lock` - Attempts to take the lock.
+    /// * `blocking` - Bits of the state that prevent `try_lock` from succeeding.
+    #[cold]
+    fn acquire(&self, try_lock: fn(&Self) -> bool, blocking: u32) {
+        loop {
+            for round in 0..SPIN_ROUNDS {
+                if try_lock(self) {
+                    return;
+                }
+                for _ in 0..1 << round {
+                    spin_loop();
+                }
+            }
+            if try_lock(self) {
+                return;
+            }
+            self.park(blocking);
+        }
+    }
+
+    /// Returns the parking bucket of this lock.
+    #[inline(always)]
+    fn bucket(&self) -> &'static (Mutex<()>, Condvar) {
+        &PARKING_LOT[(self as *const Self as usize >> 2) % BUCKETS]
+    }
+
+    /// Sleeps until the lock is released, unless it already is.
+    ///
+    /// The `PARKED` bit is only set while the bucket's mutex is held, and the
+    /// waking thread clears it before taking that mutex to notify, so a release
+    /// can never slip between the check and the wait.
+    fn park(&self, blocking: u32) {
+        let (mutex, condvar) = self.bucket();
+        let mut guard = mutex.lock().unwrap_or_else(PoisonError::into_inner);
+        let mut state = self.state.load(Relaxed);
+        loop {
+            if state & blocking == 0 {
+                return;
+            }
+            match self.state.compare_exchange_weak(state, state | PARKED, Relaxed, Relaxed) {
+                Ok(_) => break,
+                Err(current) => state = current,
+            }
+        }
+        while self.state.load(Relaxed) & PARKED != 0 {
+            guard = condvar.wait(guard).unwrap_or_else(PoisonError::into_inner);
+        }
+    }
+
+    /// Wakes every thread parked on this lock's bucket. The ones still unable to
+    /// take the lock park again.
+    #[cold]
+    fn unpark(&self) {
+        self.state.fetch_and(!PARKED, Relaxed);
+        let (mutex, condvar) = self.bucket();
+        drop(mutex.lock().unwrap_or_else(PoisonError::into_inner));
+        condvar.notify_all();
+    }
+}
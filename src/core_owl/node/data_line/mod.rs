@@ -1,5 +1,7 @@
-mod entity;
-mod data_line;
-mod data_line_impl;
+pub mod entity;
+pub mod entity_lock;
+#[allow(clippy::module_inception)]
+pub mod data_line;
+pub mod data_line_impl;
 mod entity_iter;
-pub mod test;
\ No newline at end of file
+pub mod test;
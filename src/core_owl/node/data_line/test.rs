@@ -11,7 +11,7 @@ pub fn entity_iter_link_based1(){
     for i in 0..10000{
         let li =Link{
             next:i + 1,
-            prev: prev
+            prev
         };
         arr.push(Entity::new(i, i, li, Link::default()));
         prev = i;
@@ -24,4 +24,42 @@ pub fn entity_iter_link_based1(){
         cur_idx += 1
     }
     assert!(cur_idx > 5555);
-}
\ No newline at end of file
+}
+#[test]
+pub fn entity_lock2_readers_and_writers(){
+    use std::sync::Arc;
+    use std::thread;
+    use super::entity_lock::EntityLock;
+
+    let lock = EntityLock::new();
+    assert!(lock.try_read() && lock.try_read());
+    assert_eq!(lock.readers(), 2);
+    assert!(!lock.try_write());
+    lock.read_unlock();
+    lock.read_unlock();
+    assert!(lock.try_write() && lock.is_write_locked());
+    assert!(!lock.try_read());
+    lock.write_unlock();
+
+    let entity = Arc::new(Entity::new(1u64, 0u64, Link::default(), Link::default()));
+    {
+        let first = entity.read();
+        let second = entity.read();
+        assert_eq!((*first, *second), (0, 0));
+    }
+
+    // Enough contention for threads to exhaust their spinning and park.
+    let workers: Vec<_> = (0..8).map(|_| {
+        let entity = Arc::clone(&entity);
+        thread::spawn(move || {
+            for _ in 0..20_000{
+                *entity.lock().value_mut() += 1;
+                assert!(*entity.read() > 0);
+            }
+        })
+    }).collect();
+    for worker in workers{
+        worker.join().unwrap();
+    }
+    assert_eq!(*entity.read(), 160_000);
+}
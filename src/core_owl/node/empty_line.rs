@@ -1,4 +1,5 @@
 use super::array::ARR_SIZE;
+use super::handle::Handle;
 use super::NULL_IDX;
 
 
@@ -31,6 +32,10 @@ pub struct EmptyMap {
     /// It contains 1024 `u64` values, where each bit represents an individual L3 slot.
     /// A `0` bit indicates a free slot, while a `1` bit indicates that slot is occupied.
     pub free_slots: [u64; 1024],
+
+    /// The generation of every slot, bumped every time the slot is freed, so
+    /// that `Handle`s taken before can be told apart from the slot's new occupant.
+    generations: Box<[u32]>,
 }
 
 /// Constant values used to define the final L3 mask and the lengths of the L2 and L3 caches.
@@ -48,15 +53,18 @@ impl EmptyMap {
     /// - `free_slots` is set to `u64::MAX` for each L3 block (all slots are free).
     ///
     /// The final L3 block (index 1023) is set to `FINAL_L3_MASK` to manage the last slot.
+    /// Every slot starts at generation `0`. The generations are allocated on the
+    /// heap, which keeps this from being a `const fn`.
     ///
     /// # Returns
     /// A new `EmptyMap` instance.
-    pub const fn new() -> Self {
+    pub fn new() -> Self {
         let mut map = EmptyMap {
             l1_filter: u16::MAX,
             count: 0,
             l2_filter: [u64::MAX; 16],
             free_slots: [u64::MAX; 1024],
+            generations: vec![0; ARR_SIZE as usize].into_boxed_slice(),
         };
         map.free_slots[1023] = FINAL_L3_MASK;  // Set the final L3 block mask.
         map
@@ -73,6 +81,52 @@ impl EmptyMap {
         ARR_SIZE - self.count
     }
 
+    /// Returns `true` if the slot at `idx` is free.
+    ///
+    /// # Arguments
+    /// * `idx` - The index of the slot, lower than `ARR_SIZE`.
+    #[inline(always)]
+    pub const fn is_free(&self, idx: u16) -> bool {
+        self.free_slots[(idx >> 6) as usize] & (1 << (63 - (idx & 63))) != 0
+    }
+
+    /// Returns an iterator over the indices of the occupied slots, in order.
+    pub fn occupied(&self) -> impl Iterator<Item = u16> + '_ {
+        self.free_slots.iter().enumerate().flat_map(|(block, &free)| {
+            // Bits are stored from the most significant one, a `0` marks an occupied slot.
+            let mut occupied = !free;
+            std::iter::from_fn(move || {
+                if occupied == 0 {
+                    return None;
+                }
+                let bit = occupied.leading_zeros() as u16;
+                occupied &= !(1 << (63 - bit));
+                Some(block as u16 * 64 + bit)
+            })
+        })
+        // The bits past the last slot of the final block are never free.
+        .take_while(|&idx| idx < ARR_SIZE)
+    }
+
+    /// Returns the current generation of the slot at `idx`.
+    #[inline(always)]
+    pub fn generation(&self, idx: u16) -> u32 {
+        self.generations[idx as usize]
+    }
+
+    /// Returns a handle to the current generation of the slot at `idx`.
+    #[inline(always)]
+    pub fn handle(&self, idx: u16) -> Handle {
+        Handle::new(idx, self.generations[idx as usize])
+    }
+
+    /// Returns `true` if the slot of `handle` is occupied and was not freed
+    /// since the handle was taken.
+    #[inline(always)]
+    pub fn is_live(&self, handle: Handle) -> bool {
+        !handle.is_null() && !self.is_free(handle.idx) && self.generations[handle.idx as usize] == handle.gen
+    }
+
     /// Finds and returns the index of the next available empty slot.
     ///
     /// The function uses a multi-level bitmask approach:
@@ -131,6 +185,18 @@ impl EmptyMap {
     /// # Arguments
     /// * `idx` - The index of the slot to be returned.
     pub fn return_free_idx(&mut self, idx: u16) {
+        self.release(idx);
+
+        // Decrease the count of allocated slots since one has been freed.
+        self.count -= 1;
+    }
+
+    /// Marks the slot at `idx` as free in the L1, L2 and L3 filters and starts
+    /// its next generation, without touching `count`.
+    #[inline(always)]
+    fn release(&mut self, idx: u16) {
+        self.generations[idx as usize] = self.generations[idx as usize].wrapping_add(1);
+
         // Calculate which L3 block the given index belongs to. 
         let free_slots_idx = idx >> 6;  // Div by 64 (right shift by 6 bits)
     
@@ -143,8 +209,8 @@ impl EmptyMap {
         // Check if the current L3 block is entirely empty (i.e., has no free slots).
         if *free_slot == 0 {
             // If the L3 block is empty, update the L2 filter.
-            let l2_filter_idx = (free_slots_idx >> 4) & 15;  // Calculate L2 block index (Div by 16 and mod by 16)
-            let l2_filter_bit_idx = free_slots_idx & 63;  // Get position within the `l2_filter` array
+            let l2_filter_idx = free_slots_idx >> 6;  // Calculate L2 block index (Div by 64)
+            let l2_filter_bit_idx = 63 - (free_slots_idx & 63);  // Reverse bit position within the `l2_filter` entry
     
             // If the L2 block is empty, update the L1 filter to indicate that a block in L2 is now available.
             if self.l2_filter[l2_filter_idx as usize] == 0 {
@@ -157,8 +223,25 @@ impl EmptyMap {
         
         // Set the corresponding bit in the L3 block to indicate the slot is now free.
         *free_slot |= 1 << free_slots_bit_idx;
-    
-        // Decrease the count of allocated slots since one has been freed.
-        self.count -= 1;
+    }
+
+    /// Returns a batch of indices to the pool of free slots.
+    ///
+    /// Equivalent to calling `return_free_idx` for every index, used to hand
+    /// back all the slots reclaimed by a single expiry pass at once.
+    ///
+    /// # Arguments
+    /// * `idxs` - The indices of the slots to be returned, all currently occupied.
+    pub fn return_free_idxs(&mut self, idxs: &[u16]) {
+        for &idx in idxs {
+            self.release(idx);
+        }
+        self.count -= idxs.len() as u16;
+    }
+}
+
+impl Default for EmptyMap {
+    fn default() -> Self {
+        Self::new()
     }
 }
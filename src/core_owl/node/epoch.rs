@@ -0,0 +1,145 @@
+use std::cell::Cell;
+use std::sync::atomic::{
+    fence, AtomicU64, AtomicUsize,
+    Ordering::{Relaxed, SeqCst},
+};
+use std::sync::Mutex;
+
+/// Number of reader counter stripes, so readers on different threads rarely
+/// share a cache line.
+const STRIPES: usize = 16;
+
+/// Hands out the stripe of every thread, round robin.
+static NEXT_STRIPE: AtomicUsize = AtomicUsize::new(0);
+
+thread_local! {
+    /// Stripe of the current thread, picked on its first pin.
+    static STRIPE: Cell<usize> = const { Cell::new(usize::MAX) };
+}
+
+/// Readers pinned in each of the three live epochs, padded to a cache line.
+#[repr(align(64))]
+#[derive(Default)]
+struct Stripe([AtomicUsize; 3]);
+
+/// An epoch based collector of `T`s that lock-free readers may still reference.
+///
+/// Readers `pin` the current epoch for as long as they hold references obtained
+/// from shared pointers. A writer unlinks an object so no new reader can reach
+/// it, then `retire`s it: the object is tagged with the current epoch and only
+/// dropped by `collect` once the epoch moved two steps further, which requires
+/// every reader pinned at the tagged epoch or before to have left.
+///
+/// Only three epochs are ever live, so readers are counted per epoch modulo `3`,
+/// in per-thread stripes. Pinning costs two atomic increments on a line the
+/// thread rarely shares; retiring and collecting are meant for a single writer
+/// at a time.
+pub struct Collector<T> {
+    /// Current epoch.
+    epoch: AtomicU64,
+
+    /// Readers pinned per stripe and per epoch modulo `3`.
+    pinned: Box<[Stripe]>,
+
+    /// Retired objects and the epoch they were retired in, oldest first.
+    garbage: Mutex<Vec<(u64, Box<T>)>>,
+}
+
+/// Keeps the epoch it was taken in pinned until dropped.
+///
+/// # Lifetimes
+/// - `'a`: Lifetime tied to the collector.
+pub struct Pin<'a, T> {
+    collector: &'a Collector<T>, // Collector the epoch is pinned in.
+    stripe: usize,               // Stripe counting this reader.
+    epoch: u64,                  // Pinned epoch.
+}
+
+impl<T> Collector<T> {
+    /// Creates a collector at epoch `0`, with nothing retired.
+    pub fn new() -> Self {
+        Collector {
+            epoch: AtomicU64::new(0),
+            pinned: (0..STRIPES).map(|_| Stripe::default()).collect(),
+            garbage: Mutex::new(Vec::new()),
+        }
+    }
+
+    /// Pins the current epoch, so nothing retired from now on is dropped before
+    /// the returned `Pin` is.
+    #[inline(always)]
+    pub fn pin(&self) -> Pin<'_, T> {
+        let stripe = STRIPE.with(|stripe| {
+            if stripe.get() == usize::MAX {
+                stripe.set(NEXT_STRIPE.fetch_add(1, Relaxed) % STRIPES);
+            }
+            stripe.get()
+        });
+        loop {
+            let epoch = self.epoch.load(SeqCst);
+            let counter = &self.pinned[stripe].0[(epoch % 3) as usize];
+            counter.fetch_add(1, SeqCst);
+            // The epoch may have moved on before this reader was counted, in
+            // which case a writer may not have waited for it.
+            if self.epoch.load(SeqCst) == epoch {
+                return Pin { collector: self, stripe, epoch };
+            }
+            counter.fetch_sub(1, SeqCst);
+        }
+    }
+
+    /// Hands `garbage` over to the collector, which drops it once no reader can
+    /// reference it anymore. It must already be unreachable for new readers.
+    pub fn retire(&self, garbage: Box<T>) {
+        // The unlinking stores must be visible before the epoch is read.
+        fence(SeqCst);
+        let epoch = self.epoch.load(SeqCst);
+        self.garbage.lock().unwrap().push((epoch, garbage));
+    }
+
+    /// Returns the number of readers pinned at `epoch`, or at the epochs sharing
+    /// its counters.
+    fn readers(&self, epoch: u64) -> usize {
+        self.pinned.iter().map(|stripe| stripe.0[(epoch % 3) as usize].load(SeqCst)).sum()
+    }
+
+    /// Advances the epoch as far as the pinned readers allow, then drops the
+    /// retired objects no reader can still reference.
+    ///
+    /// # Returns
+    /// The number of dropped objects.
+    pub fn collect(&self) -> usize {
+        let mut garbage = self.garbage.lock().unwrap();
+        if garbage.is_empty() {
+            return 0;
+        }
+        for _ in 0..2 {
+            let epoch = self.epoch.load(SeqCst);
+            if self.readers(epoch + 2) != 0 {
+                break;
+            }
+            self.epoch.store(epoch + 1, SeqCst);
+        }
+        let epoch = self.epoch.load(SeqCst);
+        let expired = garbage.partition_point(|&(retired, _)| retired + 2 <= epoch);
+        garbage.drain(..expired).count()
+    }
+
+    /// Returns the number of retired objects not dropped yet.
+    pub fn pending(&self) -> usize {
+        self.garbage.lock().unwrap().len()
+    }
+}
+
+impl<T> Default for Collector<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> Drop for Pin<'_, T> {
+    /// Unpins the epoch when the reader is done.
+    fn drop(&mut self) {
+        self.collector.pinned[self.stripe].0[(self.epoch % 3) as usize].fetch_sub(1, SeqCst);
+    }
+}
@@ -0,0 +1,94 @@
+use std::time::{Duration, Instant};
+
+use super::array::ARR_SIZE;
+use super::timer_wheel::TimerWheel;
+
+/// Deadline value of a slot that never expires.
+const NO_DEADLINE: u64 = 0;
+
+/// `ExpiryLine` stores the expiration deadline of every slot of a `DataLine`.
+///
+/// Deadlines are milliseconds elapsed since the line was created, `0` meaning the
+/// slot has no deadline. Every slot with a deadline is also scheduled in a
+/// `TimerWheel`, so expired entities can be collected without waiting for them to
+/// be read and without scanning the line.
+pub struct ExpiryLine {
+    /// Deadline of every slot, `NO_DEADLINE` when the slot does not expire.
+    deadlines: Box<[u64]>,
+
+    /// Instant all deadlines are relative to.
+    epoch: Instant,
+
+    /// Slots with a deadline, bucketed by when they are due.
+    wheel: TimerWheel,
+}
+
+impl ExpiryLine {
+    /// Creates a line where no slot expires.
+    pub fn new() -> Self {
+        ExpiryLine {
+            deadlines: vec![NO_DEADLINE; ARR_SIZE as usize].into_boxed_slice(),
+            epoch: Instant::now(),
+            wheel: TimerWheel::new(),
+        }
+    }
+
+    /// Returns the current time in milliseconds since the line was created.
+    #[inline(always)]
+    pub fn now(&self) -> u64 {
+        self.epoch.elapsed().as_millis() as u64
+    }
+
+    /// Makes the slot at `idx` expire `ttl` from now.
+    ///
+    /// # Arguments
+    /// * `idx` - The slot index.
+    /// * `ttl` - Time to live of the entity stored in the slot.
+    #[inline(always)]
+    pub fn set(&mut self, idx: u16, ttl: Duration) {
+        let deadline = self.now().saturating_add(ttl.as_millis() as u64).max(1);
+        self.wheel.cancel(idx);
+        self.wheel.schedule(idx, deadline);
+        self.deadlines[idx as usize] = deadline;
+    }
+
+    /// Removes the deadline of the slot at `idx`.
+    #[inline(always)]
+    pub fn clear(&mut self, idx: u16) {
+        if self.deadlines[idx as usize] != NO_DEADLINE {
+            self.wheel.cancel(idx);
+        }
+        self.deadlines[idx as usize] = NO_DEADLINE;
+    }
+
+    /// Returns `true` if the slot at `idx` has a deadline that is not after `now`.
+    #[inline(always)]
+    pub fn is_expired(&self, idx: u16, now: u64) -> bool {
+        let deadline = self.deadlines[idx as usize];
+        deadline != NO_DEADLINE && deadline <= now
+    }
+
+    /// Returns the time left before the slot at `idx` expires, if it has a deadline.
+    pub fn remaining(&self, idx: u16) -> Option<Duration> {
+        match self.deadlines[idx as usize] {
+            NO_DEADLINE => None,
+            deadline => Some(Duration::from_millis(deadline.saturating_sub(self.now()))),
+        }
+    }
+
+    /// Collects every slot that expired since the previous call.
+    ///
+    /// # Arguments
+    /// * `expired` - Receives the expired slots. They are unscheduled, but keep
+    ///   their deadline until `clear` is called.
+    pub fn collect_expired(&mut self, expired: &mut Vec<u16>) {
+        let now = self.now();
+        self.wheel.advance(now, &self.deadlines, expired);
+    }
+}
+
+impl Default for ExpiryLine {
+    fn default() -> Self {
+        Self::new()
+    }
+}
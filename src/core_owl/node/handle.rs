@@ -0,0 +1,47 @@
+use super::NULL_IDX;
+
+/// Refers to the entity stored in a slot of a `Node`, as long as the slot is not
+/// freed.
+///
+/// Every slot has a generation that the `EmptyMap` bumps whenever the slot is
+/// freed. A handle keeps the generation the slot had when it was taken, so a
+/// handle to a slot that was freed, even if it was reused right away, is stale.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Handle {
+    /// Index of the slot.
+    pub idx: u16,
+
+    /// Generation of the slot when the handle was taken.
+    pub gen: u32,
+}
+
+impl Handle {
+    /// The handle of no slot, ending chains.
+    pub const NULL: Handle = Handle { idx: NULL_IDX, gen: 0 };
+
+    /// Creates a handle to the slot at `idx` in its generation `gen`.
+    pub const fn new(idx: u16, gen: u32) -> Self {
+        Handle { idx, gen }
+    }
+
+    /// Returns `true` for `Handle::NULL`.
+    #[inline(always)]
+    pub const fn is_null(&self) -> bool {
+        self.idx == NULL_IDX
+    }
+
+    /// Packs the handle in a `u64`, as `gen << 16 | idx`.
+    #[inline(always)]
+    pub const fn to_bits(self) -> u64 {
+        (self.gen as u64) << 16 | self.idx as u64
+    }
+
+    /// Unpacks a handle packed by `to_bits`.
+    #[inline(always)]
+    pub const fn from_bits(bits: u64) -> Self {
+        Handle {
+            idx: bits as u16,
+            gen: (bits >> 16) as u32,
+        }
+    }
+}
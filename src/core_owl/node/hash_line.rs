@@ -1,9 +1,8 @@
-use std::ptr::NonNull;
-
 use super::array::{
     ARR_SIZE, // Constant representing the array size
     unsafe_array::UnsafeArray, // Custom UnsafeArray implementation
 };
+use super::NULL_IDX;
 
 /// A simple `HashLine` implementation using an `UnsafeArray<u16>` for storage.
 /// The `HashLine` stores index mappings for hashed values.
@@ -12,13 +11,26 @@ pub struct HashLine {
 }
 
 impl HashLine {
-    /// Creates a new `HashLine` instance with an `UnsafeArray` of size `ARR_SIZE`.
+    /// Creates a new `HashLine` instance with an `UnsafeArray` of size `ARR_SIZE`,
+    /// with every position initialized to `NULL_IDX` (no chain).
     pub fn new() -> Self {
         let size = ARR_SIZE as usize; // Convert array size to `usize`
-        let arr = UnsafeArray::new(size); // Initialize UnsafeArray with the given size
+        let arr = UnsafeArray::simd_default(NULL_IDX, size); // Initialize every bucket as empty
         HashLine { arr }
     }
 
+    /// Maps a hash value onto its position in the `HashLine`.
+    ///
+    /// # Arguments
+    /// * `hash_val` - The full hash of a key.
+    ///
+    /// # Returns
+    /// * The bucket position, always lower than `ARR_SIZE`.
+    #[inline(always)]
+    pub const fn hash_idx(hash_val: u64) -> usize {
+        (hash_val % ARR_SIZE as u64) as usize
+    }
+
     /// Retrieves the index stored at a given position in the `HashLine`.
     ///
     /// # Arguments
@@ -41,12 +53,12 @@ impl HashLine {
         self.arr.set(idx, val); // Store `val` at `idx`
     }
 
-    /// Sets a default value (`ARR_SIZE`) at a given position in the `HashLine`.
+    /// Sets the default value (`NULL_IDX`) at a given position in the `HashLine`.
     ///
     /// # Arguments
     /// * `idx` - The position at which to store the default value.
     #[inline(always)]
     pub fn set_default(&mut self, idx: usize) {
-        self.arr.set(idx, ARR_SIZE); // Assign `ARR_SIZE` as the default value
+        self.arr.set(idx, NULL_IDX); // Assign `NULL_IDX` as the default value
     }
 }
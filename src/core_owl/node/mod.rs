@@ -1,9 +1,23 @@
 #![allow(unused)]
-mod node;
+#[allow(clippy::module_inception)]
+pub mod node;
+pub mod node_impl;
+pub mod policy;
+pub mod admission;
+pub mod weigher;
 mod meta_data;
 pub mod empty_line;
+pub mod handle;
 mod hash_line;
+mod tag_line;
+mod expiry_line;
+mod timer_wheel;
+mod weight_line;
+pub mod epoch;
+mod read_line;
+pub mod concurrent_node;
 pub mod data_line;
 mod array;
+pub use array::ARR_SIZE;
 static NULL_IDX:u16 = u16::MAX;
-mod test;
\ No newline at end of file
+mod test;
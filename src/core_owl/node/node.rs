@@ -0,0 +1,903 @@
+use std::borrow::Borrow;
+use std::hash::Hash;
+use std::sync::Arc;
+use std::time::Duration;
+
+use super::admission::TinyLfu;
+use super::data_line::{
+    data_line::DataLine,
+    data_line_impl::DataLineImpl,
+    entity::{Entity, Link},
+};
+use super::empty_line::EmptyMap;
+use super::handle::Handle;
+use super::expiry_line::ExpiryLine;
+use super::hash_line::HashLine;
+use super::node_impl::NodeImpl;
+use super::policy::{EvictionPolicy, Lru};
+use super::read_line::ReadLine;
+use super::tag_line::TagLine;
+use super::weigher::{Budget, Weigher};
+use super::weight_line::WeightLine;
+use super::{ARR_SIZE, NULL_IDX};
+
+/// A `Node` is a fixed-capacity hash map built from three lines:
+/// - `HashLine`: maps a hash bucket to the head of its collision chain.
+/// - `DataLine`: stores the entities, linked together through `Entity.chain`.
+/// - `EmptyMap`: hands out and reclaims free slots of the `DataLine`.
+///
+/// Once every slot is taken, inserting a new key evicts the entity picked by the
+/// node's `EvictionPolicy` and reuses its slot. An optional `TinyLfu` admission
+/// filter can veto that eviction when the victim is more popular than the newcomer.
+///
+/// Entities inserted with a TTL expire lazily when looked up, and are also
+/// reclaimed in batches from the `ExpiryLine`'s timer wheel on every insert.
+///
+/// With a `Weigher`, the node is also bounded by a `Budget` on the total weight of
+/// its entries, and evicts as many entities as needed for a new one to fit.
+///
+/// With a `TagLine` in place of the `HashLine`, lookups probe SIMD groups of
+/// hash tags, and entities are not chained at all.
+///
+/// # Generics
+/// - `K`: Key type, must support hashing and equality.
+/// - `V`: Value type.
+/// - `P`: Eviction policy, `Lru` by default.
+pub struct Node<K, V, P = Lru>
+where
+    K: Hash + Eq,
+    P: EvictionPolicy<K, V>,
+{
+    /// Stores the entities.
+    data_line: DataLine<K, V>,
+
+    /// Finds the slot of a key.
+    index: Index,
+
+    /// Tracks which slots of the `data_line` are free.
+    empty_map: EmptyMap,
+
+    /// Decides which entity is evicted when the node is full.
+    policy: P,
+
+    /// Optional filter deciding whether a new entity is worth an eviction.
+    admission: Option<TinyLfu>,
+
+    /// Deadlines of the entities inserted with a TTL, created on the first one.
+    expiry: Option<ExpiryLine>,
+
+    /// Weights of the entities, when the node is bounded by a `Budget`.
+    weights: Option<WeightLine<K, V>>,
+
+    /// Entities evicted since the last call to `take_evictions`.
+    evictions: u64,
+
+    /// Mirror of the chains for lock-free readers, shared with them.
+    reads: Option<Arc<ReadLine<K, V>>>,
+}
+
+/// How a `Node` finds the slot of a key.
+enum Index {
+    /// Collision chains hanging from the buckets of a `HashLine`.
+    Chains(HashLine),
+
+    /// A SIMD probed `TagLine`, leaving the entities unchained.
+    Tags(TagLine),
+}
+
+impl<K, V, P> Node<K, V, P>
+where
+    K: Hash + Eq,
+    P: EvictionPolicy<K, V> + Default,
+{
+    /// Creates a new, empty `Node` with the default instance of its policy.
+    pub fn new() -> Self {
+        Self::with_policy(P::default())
+    }
+}
+
+impl<K, V, P> Node<K, V, P>
+where
+    K: Hash + Eq,
+    P: EvictionPolicy<K, V>,
+{
+    /// Creates a new, empty `Node` evicting through `policy`.
+    ///
+    /// # Arguments
+    /// * `policy` - The eviction policy, which must not track any entity yet.
+    pub fn with_policy(policy: P) -> Self {
+        Node {
+            data_line: DataLine::new(),
+            index: Index::Chains(HashLine::new()),
+            empty_map: EmptyMap::new(),
+            policy,
+            admission: None,
+            expiry: None,
+            weights: None,
+            evictions: 0,
+            reads: None,
+        }
+    }
+
+    /// Puts `admission` in front of the node, so that a full node only replaces
+    /// its victim with a candidate seen more often.
+    ///
+    /// # Arguments
+    /// * `admission` - The admission filter, usually `TinyLfu::new()`.
+    pub fn with_admission(mut self, admission: TinyLfu) -> Self {
+        self.admission = Some(admission);
+        self
+    }
+
+    /// Bounds the total weight of the node's entries by `budget`, on top of its
+    /// slot count.
+    ///
+    /// # Arguments
+    /// * `weigher` - Computes the weight of every inserted entry.
+    /// * `budget` - Maximum total weight, either owned by this node or shared
+    ///   with others through clones of the `Arc`.
+    pub fn with_weigher<W>(mut self, weigher: W, budget: Arc<Budget>) -> Self
+    where
+        W: Weigher<K, V> + Send + Sync + 'static,
+    {
+        self.weights = Some(WeightLine::new(Box::new(weigher), budget));
+        self
+    }
+
+    /// Indexes entities in a SwissTable like `TagLine` instead of chaining them
+    /// from the buckets of a `HashLine`, which the node drops.
+    ///
+    /// # Panics
+    /// If the node already holds entries, or shares its reads, which walk chains.
+    pub fn with_tag_line(mut self) -> Self {
+        assert!(self.is_empty(), "a tag line can only be added to an empty node");
+        assert!(self.reads.is_none(), "a node sharing its reads keeps its chains");
+        self.index = Index::Tags(TagLine::new());
+        self
+    }
+
+    /// Mirrors every entry of the node into a `ReadLine`, so that readers holding
+    /// the returned line can look entries up while the node is being written.
+    ///
+    /// # Panics
+    /// If the node already holds entries, or has a `TagLine` instead of chains.
+    pub fn share_reads(&mut self) -> Arc<ReadLine<K, V>>
+    where
+        K: Clone,
+        V: Clone,
+    {
+        assert!(self.is_empty(), "reads can only be shared by an empty node");
+        assert!(matches!(self.index, Index::Chains(_)), "reads can only be shared by a node chaining its entities");
+        self.reads
+            .get_or_insert_with(|| Arc::new(ReadLine::new(|key, value| (key.clone(), value.clone()))))
+            .clone()
+    }
+
+    /// Replays the hits lock-free readers made since the previous call on the
+    /// eviction policy.
+    pub fn replay_hits(&mut self) {
+        if let Some(reads) = &self.reads {
+            let (policy, data_line, empty_map) = (&mut self.policy, &mut self.data_line, &self.empty_map);
+            reads.drain_hits(|handle| {
+                if empty_map.is_live(handle) {
+                    policy.on_hit(data_line, handle.idx);
+                }
+            });
+        }
+    }
+
+    /// Returns the total weight of the entries stored in the node,
+    /// `0` if it has no `Weigher`.
+    #[inline(always)]
+    pub fn weighted_size(&self) -> u64 {
+        self.weights.as_ref().map_or(0, WeightLine::total)
+    }
+
+    /// Returns the number of entries stored in the node.
+    #[inline(always)]
+    pub fn len(&self) -> usize {
+        self.empty_map.count as usize
+    }
+
+    /// Returns `true` if the node holds no entries.
+    #[inline(always)]
+    pub fn is_empty(&self) -> bool {
+        self.empty_map.count == 0
+    }
+
+    /// Returns the number of entities evicted since the previous call, and
+    /// resets it.
+    #[inline(always)]
+    pub fn take_evictions(&mut self) -> u64 {
+        std::mem::take(&mut self.evictions)
+    }
+
+    /// Returns a handle to the entity of `key`, which goes stale once the entity
+    /// leaves the node, even if its slot is reused right away.
+    ///
+    /// # Returns
+    /// `None` if the key is absent or expired.
+    pub fn handle<Q>(&self, hash_val: u64, key: &Q) -> Option<Handle>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        let idx = self.find(hash_val, key)?;
+        match self.is_expired(idx) {
+            true => None,
+            false => Some(self.empty_map.handle(idx)),
+        }
+    }
+
+    /// Returns `true` if the entity of `handle` is still in the node.
+    #[inline(always)]
+    pub fn is_live(&self, handle: Handle) -> bool {
+        self.empty_map.is_live(handle)
+    }
+
+    /// Looks `key` up in the `TagLine` if the node has one, otherwise walks the
+    /// chain of the bucket of `hash_val`.
+    ///
+    /// # Returns
+    /// The slot index of the matching entity, if any.
+    fn find<Q>(&self, hash_val: u64, key: &Q) -> Option<u16>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        match &self.index {
+            Index::Tags(tags) => tags.find(hash_val, |idx| self.data_line.get_ref(idx).matches(hash_val, key)),
+            Index::Chains(hash_line) => {
+                let head = hash_line.get_idx(HashLine::hash_idx(hash_val));
+                (&mut self.data_line.entity_iter(head, false))
+                    .find(|(entity, _)| entity.matches(hash_val, key))
+                    .map(|(_, idx)| idx)
+            }
+        }
+    }
+
+    /// Removes the entity at `idx` from the policy and from its chain,
+    /// repairing the bucket head as needed.
+    /// The slot itself is not returned to the `EmptyMap`.
+    fn remove_idx(&mut self, idx: u16) -> Entity<K, V> {
+        self.policy.on_remove(&mut self.data_line, idx);
+        self.detach(idx)
+    }
+
+    /// Takes the entity at `idx` out of its chain and of every side line, once
+    /// the policy has let go of it.
+    fn detach(&mut self, idx: u16) -> Entity<K, V> {
+        let entity = self.data_line.take_idx(idx);
+        if let Some(reads) = &self.reads {
+            reads.unlink(idx, entity.chain.prev);
+        }
+        if let Some(expiry) = &mut self.expiry {
+            expiry.clear(idx);
+        }
+        if let Some(weights) = &mut self.weights {
+            weights.clear(idx);
+        }
+        match &mut self.index {
+            Index::Tags(tags) => tags.remove(idx),
+            Index::Chains(hash_line) => {
+                let hash_idx = entity.hash_idx as usize;
+                if hash_line.get_idx(hash_idx) == idx {
+                    hash_line.set_idx(hash_idx, entity.chain.next);
+                }
+            }
+        }
+        entity
+    }
+
+    /// Evicts the victim chosen by the policy to make room for an entity, and
+    /// returns its slot to the `EmptyMap`, which starts the slot's next
+    /// generation.
+    ///
+    /// # Arguments
+    /// * `candidate` - Hash of the entity room is made for, weighed against the
+    ///   victim by the admission filter, `None` if it is already stored.
+    ///
+    /// # Returns
+    /// `false` if the admission filter keeps the victim or the node holds nothing.
+    fn evict(&mut self, candidate: Option<u64>) -> bool {
+        let idx = self.policy.victim(&mut self.data_line);
+        if idx == NULL_IDX {
+            return false;
+        }
+        if let (Some(admission), Some(candidate)) = (&self.admission, candidate) {
+            if !admission.admit(candidate, idx) {
+                return false;
+            }
+        }
+        self.policy.on_evict(&mut self.data_line, idx);
+        self.detach(idx);
+        self.empty_map.return_free_idx(idx);
+        self.evictions += 1;
+        true
+    }
+
+    /// Evicts entities until `weight` more fits the node's budget, and charges it.
+    ///
+    /// # Arguments
+    /// * `candidate` - As in `evict`.
+    /// * `weight` - Weight still to be charged for the entry.
+    ///
+    /// # Returns
+    /// `false`, charging nothing, if the entry cannot fit even once every entity
+    /// of the node is gone, or if the admission filter keeps a victim.
+    fn make_room(&mut self, candidate: Option<u64>, weight: u32) -> bool {
+        while let Some(weights) = &self.weights {
+            let (budget, weight) = (weights.budget(), weight as u64);
+            if budget.try_reserve(weight) {
+                return true;
+            }
+            // Peers spending a shared budget do not starve a node below its share.
+            if weights.total() + weight <= budget.share() {
+                budget.charge(weight);
+                return true;
+            }
+            // Only this node's own entries can be evicted, don't evict any of them
+            // when even an empty node would not make enough room.
+            if weight > budget.share() && budget.used().saturating_sub(weights.total()) + weight > budget.limit() {
+                return false;
+            }
+            if !self.evict(candidate) {
+                return false;
+            }
+        }
+        true
+    }
+
+    /// Records an access of `hash_val` in the admission filter, if any.
+    #[inline(always)]
+    fn record(&mut self, hash_val: u64) {
+        if let Some(admission) = &mut self.admission {
+            admission.record(hash_val);
+        }
+    }
+}
+
+impl<K, V, P> Node<K, V, P>
+where
+    K: Hash + Eq,
+    P: EvictionPolicy<K, V>,
+{
+    /// Inserts a key-value pair that expires `ttl` from now.
+    ///
+    /// Behaves like `NodeImpl::insert`, an existing key also gets its deadline
+    /// replaced. Once expired, the entity is never returned again and its slot is
+    /// reclaimed either when it is next looked up or by `purge_expired`.
+    ///
+    /// # Returns
+    /// The previous value if the key was present and not expired.
+    pub fn insert_with_ttl(&mut self, hash_val: u64, key: K, value: V, ttl: Duration) -> Option<V> {
+        self.insert_entry(hash_val, key, value, Some(ttl)).unwrap_or(None)
+    }
+
+    /// Inserts a key-value pair like `NodeImpl::insert`, telling apart an entry
+    /// that was turned away from one that was stored.
+    ///
+    /// # Arguments
+    /// * `ttl` - Time to live of the entry as in `insert_with_ttl`, `None` for
+    ///   no expiration.
+    ///
+    /// # Returns
+    /// The previous value if the key was already present, or the key and value
+    /// back if they could not fit the budget or the admission filter kept the
+    /// victim. A present key then keeps its old value, unless the policy picked
+    /// it as a victim while making room for the new one.
+    pub fn try_insert(&mut self, hash_val: u64, key: K, value: V, ttl: Option<Duration>) -> Result<Option<V>, (K, V)> {
+        self.insert_entry(hash_val, key, value, ttl)
+    }
+
+    /// Returns the time left before the entity of `key` expires.
+    ///
+    /// # Returns
+    /// `None` if the key is absent, expired, or was inserted without a TTL.
+    pub fn ttl<Q>(&self, hash_val: u64, key: &Q) -> Option<Duration>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        let idx = self.find(hash_val, key)?;
+        match self.is_expired(idx) {
+            true => None,
+            false => self.expiry.as_ref()?.remaining(idx),
+        }
+    }
+
+    /// Reclaims every entity whose TTL elapsed since the previous purge.
+    ///
+    /// Only the timer wheel buckets that came due are visited, and the freed
+    /// slots are handed back to the `EmptyMap` in a single batch.
+    ///
+    /// # Returns
+    /// The number of reclaimed entities.
+    pub fn purge_expired(&mut self) -> usize {
+        let mut expired = Vec::new();
+        match &mut self.expiry {
+            Some(expiry) => expiry.collect_expired(&mut expired),
+            None => return 0,
+        }
+        for &idx in &expired {
+            self.remove_idx(idx);
+        }
+        self.empty_map.return_free_idxs(&expired);
+        expired.len()
+    }
+
+    /// Removes every entry whose key hash matches `pred`, dropping the expired ones.
+    ///
+    /// Used to migrate the entries of a ring arc to another node.
+    ///
+    /// # Arguments
+    /// * `rehash` - Hashes a key, only called if entities do not store their hash.
+    /// * `pred` - Tells whether the entry of a hash is removed.
+    ///
+    /// # Returns
+    /// The removed entries with their hash and the time each one had left to
+    /// live, if any.
+    pub fn drain_where<H, F>(&mut self, rehash: H, mut pred: F) -> Vec<(u64, K, V, Option<Duration>)>
+    where
+        H: Fn(&K) -> u64,
+        F: FnMut(u64) -> bool,
+    {
+        let mut drained = Vec::new();
+        for idx in 0..ARR_SIZE {
+            if self.empty_map.is_free(idx) {
+                continue;
+            }
+            let hash_val = self.data_line.get_ref(idx).hash_or(&rehash);
+            if !pred(hash_val) {
+                continue;
+            }
+            if self.is_expired(idx) {
+                self.expire(idx);
+                continue;
+            }
+            let ttl = self.expiry.as_ref().and_then(|expiry| expiry.remaining(idx));
+            let (key, value) = self.remove_idx(idx).into_inner();
+            self.empty_map.return_free_idx(idx);
+            drained.push((hash_val, key, value, ttl));
+        }
+        drained
+    }
+
+    /// Returns the key hash of every entry of the node, expired ones included.
+    ///
+    /// # Arguments
+    /// * `rehash` - Hashes a key, only called if entities do not store their hash.
+    pub fn hashes<'a, H>(&'a self, rehash: H) -> impl Iterator<Item = u64> + 'a
+    where
+        H: Fn(&K) -> u64 + 'a,
+    {
+        (0..ARR_SIZE)
+            .filter(|&idx| !self.empty_map.is_free(idx))
+            .map(move |idx| self.data_line.get_ref(idx).hash_or(&rehash))
+    }
+
+    /// Removes the entry of `key` like `NodeImpl::delete`, to migrate it to
+    /// another node.
+    ///
+    /// # Returns
+    /// The key, the value and the time the entry had left to live, if any.
+    pub fn take<Q>(&mut self, hash_val: u64, key: &Q) -> Option<(K, V, Option<Duration>)>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        let idx = self.find(hash_val, key)?;
+        if self.is_expired(idx) {
+            self.expire(idx);
+            return None;
+        }
+        let ttl = self.expiry.as_ref().and_then(|expiry| expiry.remaining(idx));
+        let (key, value) = self.remove_idx(idx).into_inner();
+        self.empty_map.return_free_idx(idx);
+        Some((key, value, ttl))
+    }
+
+    /// Retrieves the value of the entity of `handle`, reporting the hit to the
+    /// policy like `NodeImpl::get`.
+    ///
+    /// # Returns
+    /// `None` if the handle is stale or the entity expired.
+    pub fn get_by_handle(&mut self, handle: Handle) -> Option<&V> {
+        if !self.is_live(handle) {
+            return None;
+        }
+        if self.is_expired(handle.idx) {
+            self.expire(handle.idx);
+            return None;
+        }
+        self.policy.on_hit(&mut self.data_line, handle.idx);
+        Some(self.data_line.get_mut(handle.idx).value())
+    }
+
+    /// Removes the entity of `handle`.
+    ///
+    /// # Returns
+    /// The removed key-value pair, `None` if the handle is stale or the entity expired.
+    pub fn remove_by_handle(&mut self, handle: Handle) -> Option<(K, V)> {
+        if !self.is_live(handle) {
+            return None;
+        }
+        if self.is_expired(handle.idx) {
+            self.expire(handle.idx);
+            return None;
+        }
+        let entity = self.remove_idx(handle.idx);
+        self.empty_map.return_free_idx(handle.idx);
+        Some(entity.into_inner())
+    }
+
+    /// Returns the value of `key`, inserting the one made by `default` first if
+    /// the key is absent or expired. A present key counts as a hit.
+    ///
+    /// # Returns
+    /// The value, `None` if the new entity could not fit or the admission
+    /// filter kept the victim.
+    pub fn get_or_insert_with<F>(&mut self, hash_val: u64, key: K, default: F) -> Option<&V>
+    where
+        F: FnOnce() -> V,
+    {
+        let idx = match self.live_idx(hash_val, &key) {
+            Some(idx) => {
+                self.policy.on_hit(&mut self.data_line, idx);
+                idx
+            }
+            None => self.insert_new(hash_val, key, default(), None).ok()?,
+        };
+        Some(self.data_line.get_mut(idx).value())
+    }
+
+    /// Modifies the value of `key` in place, under a single lock of its entity,
+    /// keeping its TTL.
+    ///
+    /// # Returns
+    /// The modified value, `None` if the key is absent or expired, or if the
+    /// value grew too heavy for the budget and was evicted.
+    pub fn and_modify<Q, F>(&mut self, hash_val: u64, key: &Q, f: F) -> Option<&V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+        F: FnOnce(&mut V),
+    {
+        let idx = self.live_idx(hash_val, key)?;
+        f(self.data_line.lock_entity(idx).value_mut());
+        self.changed(hash_val, idx)
+    }
+
+    /// Replaces the value of `key` by what `f` computes from the current one,
+    /// under a single lock of its entity.
+    ///
+    /// `f` receives `None` if the key is absent or expired. If it returns
+    /// `None`, the entity is removed, otherwise its new value is stored, keeping
+    /// the TTL of a present key.
+    ///
+    /// # Returns
+    /// The new value, `None` if there is none or it could not be stored.
+    pub fn compute<F>(&mut self, hash_val: u64, key: K, f: F) -> Option<&V>
+    where
+        F: FnOnce(Option<&V>) -> Option<V>,
+    {
+        let idx = match self.live_idx(hash_val, &key) {
+            Some(idx) => {
+                let mut entity = self.data_line.lock_entity(idx);
+                match f(Some(entity.as_ref())) {
+                    Some(value) => entity.set_val(value),
+                    None => {
+                        drop(entity);
+                        self.remove_idx(idx);
+                        self.empty_map.return_free_idx(idx);
+                        return None;
+                    }
+                }
+                drop(entity);
+                return self.changed(hash_val, idx);
+            }
+            None => self.insert_new(hash_val, key, f(None)?, None).ok()?,
+        };
+        Some(self.data_line.get_mut(idx).value())
+    }
+
+    /// Removes `key` if `pred` holds for its value, checked under a read lock
+    /// of its entity.
+    ///
+    /// # Returns
+    /// The removed key-value pair, `None` if the key is absent, expired, or
+    /// `pred` did not hold.
+    pub fn remove_if<Q, F>(&mut self, hash_val: u64, key: &Q, pred: F) -> Option<(K, V)>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+        F: FnOnce(&V) -> bool,
+    {
+        let idx = self.live_idx(hash_val, key)?;
+        if !pred(&self.data_line.get_ref(idx).read()) {
+            return None;
+        }
+        let entity = self.remove_idx(idx);
+        self.empty_map.return_free_idx(idx);
+        Some(entity.into_inner())
+    }
+
+    /// Calls `f` on the value of `key` under a read lock of its entity, reporting
+    /// the hit to the policy like `NodeImpl::get`.
+    ///
+    /// # Returns
+    /// What `f` returned, `None` if the key is absent or expired.
+    pub fn get_with<Q, T, F>(&mut self, hash_val: u64, key: &Q, f: F) -> Option<T>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+        F: FnOnce(&V) -> T,
+    {
+        let idx = self.live_idx(hash_val, key)?;
+        self.policy.on_hit(&mut self.data_line, idx);
+        Some(f(&self.data_line.get_ref(idx).read()))
+    }
+
+    /// Looks `key` up like `NodeImpl::get` without reporting a hit, reclaiming
+    /// the entity if it expired.
+    ///
+    /// # Returns
+    /// The slot index of the live entity, if any.
+    fn live_idx<Q>(&mut self, hash_val: u64, key: &Q) -> Option<u16>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.record(hash_val);
+        let idx = self.find(hash_val, key)?;
+        match self.is_expired(idx) {
+            true => {
+                self.expire(idx);
+                None
+            }
+            false => Some(idx),
+        }
+    }
+
+    /// Reports a change of the value at `idx` made in place to the policy, the
+    /// read mirror and the weights, evicting others if the entity no longer fits
+    /// the budget.
+    ///
+    /// # Returns
+    /// The new value, `None` if the entity itself had to be evicted, or removed
+    /// because it cannot fit at all.
+    fn changed(&mut self, hash_val: u64, idx: u16) -> Option<&V> {
+        self.policy.on_hit(&mut self.data_line, idx);
+        let (key, value) = self.data_line.get_mut(idx).key_value();
+        if let Some(reads) = &self.reads {
+            reads.replace(idx, key, value);
+        }
+        if let Some(weights) = &self.weights {
+            let (old, new) = (weights.get(idx), weights.weigh(key, value));
+            if !self.reweigh(idx, old, new) {
+                if !self.empty_map.is_free(idx) {
+                    self.expire(idx);
+                }
+                return None;
+            }
+        }
+        Some(self.data_line.get_mut(idx).value())
+    }
+
+    /// Changes the weight of the entity at `idx` from `old` to `new`, evicting
+    /// others if it grew past the budget. The entity is already stored, so the
+    /// admission filter has no say.
+    ///
+    /// # Returns
+    /// `false` if it cannot fit, its weight left as it was, or if it was evicted
+    /// itself.
+    fn reweigh(&mut self, idx: u16, old: u32, new: u32) -> bool {
+        if new > old && !self.make_room(None, new - old) {
+            return false;
+        }
+        let weights = self.weights.as_mut().expect("only weighted nodes reweigh entities");
+        if self.empty_map.is_free(idx) {
+            weights.budget().refund((new - old) as u64);
+            return false;
+        }
+        weights.set(idx, new);
+        true
+    }
+
+    /// Shared implementation of `insert`, `insert_with_ttl` and `try_insert`.
+    fn insert_entry(&mut self, hash_val: u64, key: K, value: V, ttl: Option<Duration>) -> Result<Option<V>, (K, V)> {
+        if self.expiry.is_some() {
+            self.purge_expired();
+        }
+        self.record(hash_val);
+        if let Some(idx) = self.find(hash_val, &key) {
+            match self.is_expired(idx) {
+                true => self.expire(idx),
+                false => {
+                    self.policy.on_hit(&mut self.data_line, idx);
+                    if let Some(weights) = &self.weights {
+                        // The new value may weigh more than the old one, and evict
+                        // others, or the entity itself, which then comes back anew.
+                        let (old, new) = (weights.get(idx), weights.weigh(&key, &value));
+                        if !self.reweigh(idx, old, new) {
+                            return match self.empty_map.is_free(idx) {
+                                true => self.insert_new(hash_val, key, value, ttl).map(|_| None),
+                                false => Err((key, value)),
+                            };
+                        }
+                    }
+                    self.set_ttl(idx, ttl);
+                    let previous = self.data_line.lock_entity(idx).replace_val(value);
+                    if let Some(reads) = &self.reads {
+                        let (key, value) = self.data_line.get_mut(idx).key_value();
+                        reads.replace(idx, key, value);
+                    }
+                    return Ok(Some(previous));
+                }
+            }
+        }
+        self.insert_new(hash_val, key, value, ttl).map(|_| None)
+    }
+
+    /// Stores an entity for `key`, which must not be in the node, evicting
+    /// others as needed.
+    ///
+    /// # Returns
+    /// The slot of the new entity, or the key and value back if they could not
+    /// fit or the admission filter kept the victim.
+    fn insert_new(&mut self, hash_val: u64, key: K, value: V, ttl: Option<Duration>) -> Result<u16, (K, V)> {
+        let hash_idx = HashLine::hash_idx(hash_val);
+        let weight = self.weights.as_ref().map_or(0, |weights| weights.weigh(&key, &value));
+        if !self.make_room(Some(hash_val), weight) {
+            return Err((key, value));
+        }
+        if self.empty_map.get_empty_count() == 0 && !self.evict(Some(hash_val)) {
+            if let Some(weights) = &self.weights {
+                weights.budget().refund(weight as u64);
+            }
+            return Err((key, value));
+        }
+        let idx = self.empty_map.get_empty_idx();
+
+        // Push the new entity in front of the existing chain, if entities are chained.
+        let chain = match &mut self.index {
+            Index::Tags(tags) => {
+                tags.insert(hash_val, idx);
+                Link::default()
+            }
+            Index::Chains(hash_line) => {
+                let head = hash_line.get_idx(hash_idx);
+                if head != NULL_IDX {
+                    self.data_line.get_mut(head).chain.prev = idx;
+                }
+                hash_line.set_idx(hash_idx, idx);
+                Link {
+                    prev: NULL_IDX,
+                    next: head,
+                }
+            }
+        };
+        let mut entity = Entity::new(key, value, Link::default(), chain);
+        entity.hash_idx = hash_idx as u16;
+        entity.set_hash(hash_val);
+        self.data_line.set(idx, entity);
+        if let Some(reads) = &self.reads {
+            let (key, value) = self.data_line.get_mut(idx).key_value();
+            reads.link(hash_idx, self.empty_map.handle(idx), key, value);
+        }
+        self.set_ttl(idx, ttl);
+        self.policy.on_insert(&mut self.data_line, idx, hash_val);
+        if let Some(admission) = &mut self.admission {
+            admission.track(idx, hash_val);
+        }
+        if let Some(weights) = &mut self.weights {
+            weights.set(idx, weight);
+        }
+        Ok(idx)
+    }
+
+    /// Sets or clears the deadline of the slot at `idx`, creating the
+    /// `ExpiryLine` on the first TTL.
+    fn set_ttl(&mut self, idx: u16, ttl: Option<Duration>) {
+        match (ttl, &mut self.expiry) {
+            (Some(ttl), Some(expiry)) => expiry.set(idx, ttl),
+            (Some(ttl), None) => self.expiry.insert(ExpiryLine::new()).set(idx, ttl),
+            (None, Some(expiry)) => expiry.clear(idx),
+            (None, None) => {}
+        }
+    }
+
+    /// Returns `true` if the entity at `idx` has outlived its TTL.
+    #[inline(always)]
+    fn is_expired(&self, idx: u16) -> bool {
+        match &self.expiry {
+            Some(expiry) => expiry.is_expired(idx, expiry.now()),
+            None => false,
+        }
+    }
+
+    /// Drops the expired entity at `idx` and frees its slot.
+    fn expire(&mut self, idx: u16) {
+        self.remove_idx(idx);
+        self.empty_map.return_free_idx(idx);
+    }
+}
+
+impl<K, V, P> Drop for Node<K, V, P>
+where
+    K: Hash + Eq,
+    P: EvictionPolicy<K, V>,
+{
+    /// Drops every entity still stored, exactly once, since the `DataLine`
+    /// does not know which of its slots are occupied.
+    fn drop(&mut self) {
+        let (empty_map, data_line) = (&self.empty_map, &mut self.data_line);
+        for idx in empty_map.occupied() {
+            data_line.drop_idx(idx);
+        }
+    }
+}
+
+impl<K, V, P> Default for Node<K, V, P>
+where
+    K: Hash + Eq,
+    P: EvictionPolicy<K, V> + Default,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K, V, P> NodeImpl<K, V> for Node<K, V, P>
+where
+    K: Hash + Eq,
+    P: EvictionPolicy<K, V>,
+{
+    /// Inserts a key-value pair into the node, without expiration.
+    ///
+    /// If the key already exists its value is overwritten in place, otherwise the
+    /// entity takes a free slot and becomes the new head of its bucket's chain.
+    /// When the node is full, or its budget cannot take the new entity's weight,
+    /// the policy's victims are evicted first, unless the admission filter rejects
+    /// the new entity, in which case it is dropped. A new value of an existing key
+    /// that cannot fit the budget is dropped too, leaving the old one in place.
+    /// Either way `None` is returned, use `Node::try_insert` to tell a rejection
+    /// from a fresh insert.
+    fn insert(&mut self, hash_val: u64, key: K, value: V) -> Option<V> {
+        self.insert_entry(hash_val, key, value, None).unwrap_or(None)
+    }
+
+    /// Retrieves the value for a given key from the node,
+    /// reporting the hit to the policy. Expired entities are reclaimed on the spot.
+    fn get<Q>(&mut self, hash_val: u64, key: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.record(hash_val);
+        let idx = self.find(hash_val, key)?;
+        if self.is_expired(idx) {
+            self.expire(idx);
+            return None;
+        }
+        self.policy.on_hit(&mut self.data_line, idx);
+        Some(self.data_line.get_mut(idx).value())
+    }
+
+    /// Deletes a key-value pair from the node, repairing the collision chain
+    /// and returning the freed slot to the `EmptyMap`.
+    fn delete<Q>(&mut self, hash_val: u64, key: &Q) -> Option<(K, V)>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        let idx = self.find(hash_val, key)?;
+        if self.is_expired(idx) {
+            self.expire(idx);
+            return None;
+        }
+        let entity = self.remove_idx(idx);
+        self.empty_map.return_free_idx(idx);
+        Some(entity.into_inner())
+    }
+}
@@ -0,0 +1,23 @@
+use std::borrow::Borrow;
+use std::hash::Hash;
+
+pub trait NodeImpl<K: Hash + Eq, V> {
+    /// Inserts a key-value pair into the node.
+    /// Returns the previous value if the key was already present.
+    fn insert(&mut self, hash_val: u64, key: K, value: V) -> Option<V>;
+
+    /// Retrieves the value associated with the given key, or any borrowed form
+    /// of it hashing like it, as in `HashMap::get`.
+    fn get<Q>(&mut self, hash_val: u64, key: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized;
+
+    /// Deletes the key-value pair associated with the given key, or any
+    /// borrowed form of it.
+    /// Returns an `Option` containing the removed key-value pair if it existed.
+    fn delete<Q>(&mut self, hash_val: u64, key: &Q) -> Option<(K, V)>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized;
+}
@@ -0,0 +1,90 @@
+use std::hash::Hash;
+
+use super::super::array::ARR_SIZE;
+use super::super::data_line::{data_line::DataLine, data_line_impl::DataLineImpl};
+use super::super::NULL_IDX;
+use super::EvictionPolicy;
+
+/// Number of `u64` words needed to hold one bit per slot.
+const RESIDENT_WORDS: usize = (ARR_SIZE as usize).div_ceil(64);
+
+/// CLOCK: a hand sweeps the `DataLine` slot by slot, giving every visited
+/// entity a second chance by clearing its flag, and evicts the first entity
+/// that was not visited.
+///
+/// The policy never uses `Entity.link`; it only tracks which slots are resident.
+#[derive(Debug)]
+pub struct Clock {
+    /// One bit per slot, set while the slot holds an entity.
+    resident: Box<[u64; RESIDENT_WORDS]>,
+
+    /// Number of resident entities.
+    len: u16,
+
+    /// Slot the next sweep starts from.
+    hand: u16,
+}
+
+impl Clock {
+    /// Creates the policy with no resident entity.
+    pub fn new() -> Self {
+        Clock {
+            resident: Box::new([0; RESIDENT_WORDS]),
+            len: 0,
+            hand: 0,
+        }
+    }
+
+    /// Finds the first resident slot at or after `from`, wrapping around the line.
+    ///
+    /// The caller must make sure at least one slot is resident.
+    fn next_resident(&self, from: u16) -> u16 {
+        let mut word = from as usize >> 6;
+        let mut bits = self.resident[word] & (u64::MAX << (from & 63));
+        while bits == 0 {
+            word = (word + 1) % RESIDENT_WORDS;
+            bits = self.resident[word];
+        }
+        (word * 64) as u16 + bits.trailing_zeros() as u16
+    }
+}
+
+impl Default for Clock {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K, V> EvictionPolicy<K, V> for Clock
+where
+    K: Hash + Eq,
+{
+    #[inline(always)]
+    fn on_insert(&mut self, _line: &mut DataLine<K, V>, idx: u16, _hash_val: u64) {
+        self.resident[idx as usize >> 6] |= 1 << (idx & 63);
+        self.len += 1;
+    }
+
+    #[inline(always)]
+    fn on_hit(&mut self, line: &mut DataLine<K, V>, idx: u16) {
+        line.get_ref(idx).mark_visited();
+    }
+
+    #[inline(always)]
+    fn on_remove(&mut self, _line: &mut DataLine<K, V>, idx: u16) {
+        self.resident[idx as usize >> 6] &= !(1 << (idx & 63));
+        self.len -= 1;
+    }
+
+    fn victim(&mut self, line: &mut DataLine<K, V>) -> u16 {
+        if self.len == 0 {
+            return NULL_IDX;
+        }
+        let mut idx = self.next_resident(self.hand);
+        while line.get_ref(idx).clear_visited() {
+            idx = self.next_resident((idx + 1) % ARR_SIZE);
+        }
+        self.hand = (idx + 1) % ARR_SIZE;
+        idx
+    }
+}
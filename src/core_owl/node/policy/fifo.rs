@@ -0,0 +1,43 @@
+use std::hash::Hash;
+
+use super::super::data_line::data_line::DataLine;
+use super::{EvictionPolicy, LinkList};
+
+/// First in, first out: entities are evicted in insertion order, hits change nothing.
+#[derive(Debug, Default)]
+pub struct Fifo {
+    /// Entities ordered from the most recently inserted to the oldest.
+    list: LinkList,
+}
+
+impl Fifo {
+    /// Creates the policy with an empty list.
+    pub const fn new() -> Self {
+        Fifo {
+            list: LinkList::new(),
+        }
+    }
+}
+
+impl<K, V> EvictionPolicy<K, V> for Fifo
+where
+    K: Hash + Eq,
+{
+    #[inline(always)]
+    fn on_insert(&mut self, line: &mut DataLine<K, V>, idx: u16, _hash_val: u64) {
+        self.list.push_front(line, idx);
+    }
+
+    #[inline(always)]
+    fn on_hit(&mut self, _line: &mut DataLine<K, V>, _idx: u16) {}
+
+    #[inline(always)]
+    fn on_remove(&mut self, line: &mut DataLine<K, V>, idx: u16) {
+        self.list.unlink(line, idx);
+    }
+
+    #[inline(always)]
+    fn victim(&mut self, _line: &mut DataLine<K, V>) -> u16 {
+        self.list.tail
+    }
+}
@@ -0,0 +1,93 @@
+/// Number of entries sharing one bucket of the table.
+const WAYS: usize = 4;
+
+/// A bounded, lossy set of recently evicted hash fingerprints.
+///
+/// Ghosts carry no key nor value, only a 32-bit fingerprint and the insertion
+/// stamp. A ghost is alive while fewer than `capacity` ghosts were inserted after
+/// it, which makes the table behave like a FIFO of `capacity` entries without
+/// keeping any list. When a bucket is full the oldest ghost of that bucket is
+/// overwritten, so a ghost may be forgotten slightly early but never invented.
+#[derive(Debug)]
+pub struct GhostTable {
+    /// `WAYS` consecutive `(fingerprint, stamp)` entries per bucket, fingerprint `0` meaning empty.
+    entries: Box<[(u32, u32)]>,
+
+    /// Number of buckets minus one, the number of buckets being a power of two.
+    mask: usize,
+
+    /// Stamp given to the next inserted ghost.
+    clock: u32,
+
+    /// Number of most recent insertions that stay alive.
+    capacity: u32,
+}
+
+impl GhostTable {
+    /// Creates an empty table remembering up to `capacity` ghosts.
+    ///
+    /// # Arguments
+    /// * `capacity` - Number of most recent ghosts to keep alive.
+    pub fn new(capacity: u16) -> Self {
+        let buckets = (capacity as usize).div_ceil(WAYS).max(1).next_power_of_two();
+        GhostTable {
+            entries: vec![(0, 0); buckets * WAYS].into_boxed_slice(),
+            mask: buckets - 1,
+            clock: 0,
+            capacity: capacity as u32,
+        }
+    }
+
+    /// Derives the fingerprint stored for `hash_val`, never `0`.
+    #[inline(always)]
+    pub const fn fingerprint(hash_val: u64) -> u32 {
+        match (hash_val >> 32) as u32 {
+            0 => 1,
+            fingerprint => fingerprint,
+        }
+    }
+
+    /// Returns the entries of the bucket owning `fingerprint`.
+    #[inline(always)]
+    fn bucket(&mut self, fingerprint: u32) -> &mut [(u32, u32)] {
+        let bucket = (fingerprint as usize).wrapping_mul(0x9E37_79B9) >> 7 & self.mask;
+        &mut self.entries[bucket * WAYS..(bucket + 1) * WAYS]
+    }
+
+    /// Remembers `fingerprint` as the most recent ghost.
+    pub fn insert(&mut self, fingerprint: u32) {
+        let clock = self.clock;
+        self.clock = self.clock.wrapping_add(1);
+        let bucket = self.bucket(fingerprint);
+        // Reuse the slot of the same fingerprint if present, otherwise the oldest one.
+        let slot = bucket
+            .iter()
+            .position(|entry| entry.0 == fingerprint)
+            .unwrap_or_else(|| {
+                (0..WAYS)
+                    .max_by_key(|way| match bucket[*way] {
+                        (0, _) => u32::MAX,
+                        (_, stamp) => clock.wrapping_sub(stamp),
+                    })
+                    .unwrap_or(0)
+            });
+        bucket[slot] = (fingerprint, clock);
+    }
+
+    /// Removes `fingerprint` from the table.
+    ///
+    /// # Returns
+    /// `true` if `fingerprint` was a live ghost.
+    pub fn remove(&mut self, fingerprint: u32) -> bool {
+        let (clock, capacity) = (self.clock, self.capacity);
+        let bucket = self.bucket(fingerprint);
+        match bucket.iter().position(|entry| entry.0 == fingerprint) {
+            Some(slot) => {
+                let alive = clock.wrapping_sub(bucket[slot].1) <= capacity;
+                bucket[slot] = (0, 0);
+                alive
+            }
+            None => false,
+        }
+    }
+}
@@ -0,0 +1,93 @@
+use std::hash::Hash;
+
+use super::super::data_line::{
+    data_line::DataLine,
+    data_line_impl::{ChainLinker, DataLineImpl},
+    entity::Link,
+};
+use super::super::NULL_IDX;
+
+/// A doubly linked list threaded through the `Entity.link` fields of a `DataLine`.
+///
+/// The list itself only stores its two ends, every other connection lives in
+/// the entities.
+#[derive(Debug, Clone, Copy)]
+pub struct LinkList {
+    /// First entity of the list.
+    pub head: u16,
+
+    /// Last entity of the list.
+    pub tail: u16,
+}
+
+impl LinkList {
+    /// Creates an empty list.
+    pub const fn new() -> Self {
+        LinkList {
+            head: NULL_IDX,
+            tail: NULL_IDX,
+        }
+    }
+
+    /// Returns `true` if the list holds no entity.
+    #[inline(always)]
+    pub const fn is_empty(&self) -> bool {
+        self.head == NULL_IDX
+    }
+}
+
+impl Default for LinkList {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl LinkList {
+    /// Pushes the entity at `idx` in front of the list.
+    /// The entity must not currently be part of any list.
+    pub fn push_front<K, V>(&mut self, line: &mut DataLine<K, V>, idx: u16)
+    where
+        K: Hash + Eq,
+    {
+        let head = self.head;
+        line.get_mut(idx).link = Link {
+            prev: NULL_IDX,
+            next: head,
+        };
+        match head == NULL_IDX {
+            true => self.tail = idx,
+            false => line.get_mut(head).link.prev = idx,
+        }
+        self.head = idx;
+    }
+
+    /// Detaches the entity at `idx` from the list and clears its link.
+    pub fn unlink<K, V>(&mut self, line: &mut DataLine<K, V>, idx: u16)
+    where
+        K: Hash + Eq,
+    {
+        let link = line.get_ref(idx).link;
+        unsafe {
+            line.link_cl(line.as_ptr(), line.lock_entity(idx), true);
+        }
+        if self.head == idx {
+            self.head = link.next;
+        }
+        if self.tail == idx {
+            self.tail = link.prev;
+        }
+        line.get_mut(idx).link = Link::default();
+    }
+
+    /// Moves the entity at `idx`, which must be part of the list, to its front.
+    pub fn move_front<K, V>(&mut self, line: &mut DataLine<K, V>, idx: u16)
+    where
+        K: Hash + Eq,
+    {
+        if self.head == idx {
+            return;
+        }
+        self.unlink(line, idx);
+        self.push_front(line, idx);
+    }
+}
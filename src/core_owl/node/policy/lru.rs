@@ -0,0 +1,45 @@
+use std::hash::Hash;
+
+use super::super::data_line::data_line::DataLine;
+use super::{EvictionPolicy, LinkList};
+
+/// Least recently used: a hit moves the entity to the front, the tail is evicted.
+#[derive(Debug, Default)]
+pub struct Lru {
+    /// Entities ordered from the most recently inserted or used to the oldest.
+    list: LinkList,
+}
+
+impl Lru {
+    /// Creates the policy with an empty list.
+    pub const fn new() -> Self {
+        Lru {
+            list: LinkList::new(),
+        }
+    }
+}
+
+impl<K, V> EvictionPolicy<K, V> for Lru
+where
+    K: Hash + Eq,
+{
+    #[inline(always)]
+    fn on_insert(&mut self, line: &mut DataLine<K, V>, idx: u16, _hash_val: u64) {
+        self.list.push_front(line, idx);
+    }
+
+    #[inline(always)]
+    fn on_hit(&mut self, line: &mut DataLine<K, V>, idx: u16) {
+        self.list.move_front(line, idx);
+    }
+
+    #[inline(always)]
+    fn on_remove(&mut self, line: &mut DataLine<K, V>, idx: u16) {
+        self.list.unlink(line, idx);
+    }
+
+    #[inline(always)]
+    fn victim(&mut self, _line: &mut DataLine<K, V>) -> u16 {
+        self.list.tail
+    }
+}
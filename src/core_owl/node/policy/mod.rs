@@ -0,0 +1,65 @@
+//! Eviction policies a `Node` can be built with.
+//!
+//! A policy is notified through hooks whenever the node inserts, reads or removes
+//! an entity, and is asked for a `victim` once the node has no free slot left.
+//! List based policies keep their order in the `u16` `Entity.link` fields, so no
+//! policy needs per-entry heap memory.
+
+mod link_list;
+mod lru;
+mod mru;
+mod fifo;
+mod random;
+mod sieve;
+mod clock;
+mod ghost;
+mod s3_fifo;
+mod test;
+
+use std::hash::Hash;
+
+use super::data_line::data_line::DataLine;
+
+pub use link_list::LinkList;
+pub use lru::Lru;
+pub use mru::Mru;
+pub use fifo::Fifo;
+pub use random::Random;
+pub use sieve::Sieve;
+pub use clock::Clock;
+pub use ghost::GhostTable;
+pub use s3_fifo::S3Fifo;
+
+/// Decides which entity a full `Node` gives up to make room for a new one.
+///
+/// Every hook receives the `DataLine` of the node and the slot index of the
+/// entity concerned. Hooks are always called while the entity is still stored
+/// in its slot.
+pub trait EvictionPolicy<K, V>
+where
+    K: Hash + Eq,
+{
+    /// Called after a new entity of hash `hash_val` has been written into slot `idx`.
+    fn on_insert(&mut self, line: &mut DataLine<K, V>, idx: u16, hash_val: u64);
+
+    /// Called when the entity in slot `idx` is read or overwritten.
+    fn on_hit(&mut self, line: &mut DataLine<K, V>, idx: u16);
+
+    /// Called right before the entity in slot `idx` leaves the node,
+    /// whether it was deleted or evicted.
+    fn on_remove(&mut self, line: &mut DataLine<K, V>, idx: u16);
+
+    /// Called instead of `on_remove` when the entity in slot `idx` leaves the
+    /// node as the victim of an eviction.
+    fn on_evict(&mut self, line: &mut DataLine<K, V>, idx: u16) {
+        self.on_remove(line, idx);
+    }
+
+    /// Picks the slot to evict. The policy may reorganise its own links while
+    /// searching, but must not remove the victim, nor treat it as evicted: the
+    /// admission filter may still keep it, `on_evict` is only called otherwise.
+    ///
+    /// # Returns
+    /// The slot index of the victim, or `NULL_IDX` if the policy tracks nothing.
+    fn victim(&mut self, line: &mut DataLine<K, V>) -> u16;
+}
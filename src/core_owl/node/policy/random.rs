@@ -0,0 +1,85 @@
+use std::collections::hash_map::RandomState;
+use std::hash::{BuildHasher, Hash};
+
+use super::super::data_line::{data_line::DataLine, data_line_impl::DataLineImpl};
+use super::super::NULL_IDX;
+use super::EvictionPolicy;
+
+/// Random eviction: every resident entity is equally likely to be the victim.
+///
+/// Resident slots are kept densely packed in `slots` so a victim can be drawn in
+/// O(1). Each entity remembers its position in `slots` through `Entity.link.prev`,
+/// which keeps removals O(1) as well.
+#[derive(Debug)]
+pub struct Random {
+    /// Densely packed indices of every resident entity.
+    slots: Vec<u16>,
+
+    /// State of the xorshift generator, never zero.
+    state: u64,
+}
+
+impl Random {
+    /// Creates the policy with a fixed seed, useful for reproducible runs.
+    ///
+    /// # Arguments
+    /// * `seed` - Seed of the generator, `0` is replaced by a non zero constant.
+    pub fn with_seed(seed: u64) -> Self {
+        Random {
+            slots: Vec::new(),
+            state: match seed {
+                0 => 0x9E37_79B9_7F4A_7C15,
+                seed => seed,
+            },
+        }
+    }
+
+    /// Advances the xorshift64* generator.
+    #[inline(always)]
+    fn next_u64(&mut self) -> u64 {
+        self.state ^= self.state >> 12;
+        self.state ^= self.state << 25;
+        self.state ^= self.state >> 27;
+        self.state.wrapping_mul(0x2545_F491_4F6C_DD1D)
+    }
+}
+
+impl Default for Random {
+    /// Creates the policy with a seed drawn from the process' random state.
+    fn default() -> Self {
+        Self::with_seed(RandomState::new().hash_one(0u64))
+    }
+}
+
+impl<K, V> EvictionPolicy<K, V> for Random
+where
+    K: Hash + Eq,
+{
+    #[inline(always)]
+    fn on_insert(&mut self, line: &mut DataLine<K, V>, idx: u16, _hash_val: u64) {
+        line.get_mut(idx).link.prev = self.slots.len() as u16;
+        self.slots.push(idx);
+    }
+
+    #[inline(always)]
+    fn on_hit(&mut self, _line: &mut DataLine<K, V>, _idx: u16) {}
+
+    fn on_remove(&mut self, line: &mut DataLine<K, V>, idx: u16) {
+        let pos = line.get_ref(idx).link.prev as usize;
+        self.slots.swap_remove(pos);
+        if let Some(&moved) = self.slots.get(pos) {
+            line.get_mut(moved).link.prev = pos as u16;
+        }
+        line.get_mut(idx).link.prev = NULL_IDX;
+    }
+
+    fn victim(&mut self, _line: &mut DataLine<K, V>) -> u16 {
+        match self.slots.len() {
+            0 => NULL_IDX,
+            len => {
+                let pos = self.next_u64() % len as u64;
+                self.slots[pos as usize]
+            }
+        }
+    }
+}
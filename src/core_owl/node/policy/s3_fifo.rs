@@ -0,0 +1,155 @@
+use std::hash::Hash;
+
+use super::super::array::ARR_SIZE;
+use super::super::data_line::data_line::DataLine;
+use super::super::NULL_IDX;
+use super::ghost::GhostTable;
+use super::{EvictionPolicy, LinkList};
+
+/// Bit of `S3Fifo::meta` set while the entity lives in the main queue.
+const IN_MAIN: u8 = 0b1000_0000;
+
+/// Mask of the access counter stored in `S3Fifo::meta`.
+const FREQ_MASK: u8 = 0b0000_0011;
+
+/// S3-FIFO: a small probationary FIFO, a main FIFO and a ghost FIFO.
+///
+/// New entities enter the small queue, which holds about a tenth of the node.
+/// Entities leaving the small queue move on to the main queue if they were hit,
+/// otherwise they are evicted and their fingerprint becomes a ghost. A new
+/// entity whose fingerprint is still a ghost goes straight to the main queue.
+/// The main queue evicts like CLOCK: entities with a non-zero counter are
+/// reinserted at its front with the counter decremented.
+///
+/// Both resident queues are threaded through `Entity.link`; the per-slot
+/// counters and fingerprints live in side tables of `ARR_SIZE` entries, so a hit
+/// only writes one byte and never touches a neighbouring entity.
+#[derive(Debug)]
+pub struct S3Fifo {
+    /// Probationary queue, newest first.
+    small: LinkList,
+
+    /// Protected queue, newest first.
+    main: LinkList,
+
+    /// Number of entities in `small`.
+    small_len: u16,
+
+    /// Size `small` is allowed to reach before it evicts.
+    small_target: u16,
+
+    /// Per-slot queue bit and saturating access counter.
+    meta: Box<[u8]>,
+
+    /// Per-slot fingerprint of the resident entity, turned into a ghost on eviction.
+    fingerprints: Box<[u32]>,
+
+    /// Fingerprints of entities recently evicted from `small`.
+    ghost: GhostTable,
+}
+
+impl S3Fifo {
+    /// Creates the policy with the usual 10% small queue and a ghost queue as
+    /// large as the main queue.
+    pub fn new() -> Self {
+        Self::with_small_ratio(0.1)
+    }
+
+    /// Creates the policy with a small queue of `ratio * ARR_SIZE` entities.
+    ///
+    /// # Arguments
+    /// * `ratio` - Share of the node given to the small queue, clamped to `0.0..=1.0`.
+    pub fn with_small_ratio(ratio: f64) -> Self {
+        let small_target = ((ARR_SIZE as f64 * ratio.clamp(0.0, 1.0)) as u16).max(1);
+        S3Fifo {
+            small: LinkList::new(),
+            main: LinkList::new(),
+            small_len: 0,
+            small_target,
+            meta: vec![0; ARR_SIZE as usize].into_boxed_slice(),
+            fingerprints: vec![0; ARR_SIZE as usize].into_boxed_slice(),
+            ghost: GhostTable::new(ARR_SIZE - small_target),
+        }
+    }
+}
+
+impl Default for S3Fifo {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K, V> EvictionPolicy<K, V> for S3Fifo
+where
+    K: Hash + Eq,
+{
+    fn on_insert(&mut self, line: &mut DataLine<K, V>, idx: u16, hash_val: u64) {
+        let fingerprint = GhostTable::fingerprint(hash_val);
+        self.fingerprints[idx as usize] = fingerprint;
+        match self.ghost.remove(fingerprint) {
+            true => {
+                self.meta[idx as usize] = IN_MAIN;
+                self.main.push_front(line, idx);
+            }
+            false => {
+                self.meta[idx as usize] = 0;
+                self.small.push_front(line, idx);
+                self.small_len += 1;
+            }
+        }
+    }
+
+    #[inline(always)]
+    fn on_hit(&mut self, _line: &mut DataLine<K, V>, idx: u16) {
+        let meta = &mut self.meta[idx as usize];
+        if *meta & FREQ_MASK != FREQ_MASK {
+            *meta += 1;
+        }
+    }
+
+    fn on_remove(&mut self, line: &mut DataLine<K, V>, idx: u16) {
+        match self.meta[idx as usize] & IN_MAIN != 0 {
+            true => self.main.unlink(line, idx),
+            false => {
+                self.small.unlink(line, idx);
+                self.small_len -= 1;
+            }
+        }
+    }
+
+    fn on_evict(&mut self, line: &mut DataLine<K, V>, idx: u16) {
+        if self.meta[idx as usize] & IN_MAIN == 0 {
+            self.ghost.insert(self.fingerprints[idx as usize]);
+        }
+        self.on_remove(line, idx);
+    }
+
+    fn victim(&mut self, line: &mut DataLine<K, V>) -> u16 {
+        loop {
+            let from_small = !self.small.is_empty()
+                && (self.small_len >= self.small_target || self.main.is_empty());
+            if from_small {
+                let idx = self.small.tail;
+                if self.meta[idx as usize] & FREQ_MASK == 0 {
+                    return idx;
+                }
+                // Hit while on probation: promote to the main queue.
+                self.small.unlink(line, idx);
+                self.small_len -= 1;
+                self.meta[idx as usize] = IN_MAIN;
+                self.main.push_front(line, idx);
+            } else {
+                let idx = self.main.tail;
+                if idx == NULL_IDX {
+                    return NULL_IDX;
+                }
+                let meta = &mut self.meta[idx as usize];
+                if *meta & FREQ_MASK == 0 {
+                    return idx;
+                }
+                *meta -= 1;
+                self.main.move_front(line, idx);
+            }
+        }
+    }
+}
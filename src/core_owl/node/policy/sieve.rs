@@ -0,0 +1,75 @@
+use std::hash::Hash;
+
+use super::super::data_line::{data_line::DataLine, data_line_impl::DataLineImpl};
+use super::super::NULL_IDX;
+use super::{EvictionPolicy, LinkList};
+
+/// SIEVE: entities sit in insertion order and a hit only sets their visited flag.
+///
+/// At eviction time a hand walks from the oldest entity towards the newest,
+/// clearing visited flags, and stops at the first entity that was not visited.
+/// Survivors keep their position, so neither hits nor evictions reorder the list.
+#[derive(Debug)]
+pub struct Sieve {
+    /// Entities ordered from the most recently inserted to the oldest.
+    list: LinkList,
+
+    /// Entity the next sweep starts from, `NULL_IDX` to start from the tail.
+    hand: u16,
+}
+
+impl Sieve {
+    /// Creates the policy with an empty list.
+    pub const fn new() -> Self {
+        Sieve {
+            list: LinkList::new(),
+            hand: NULL_IDX,
+        }
+    }
+}
+
+impl Default for Sieve {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K, V> EvictionPolicy<K, V> for Sieve
+where
+    K: Hash + Eq,
+{
+    #[inline(always)]
+    fn on_insert(&mut self, line: &mut DataLine<K, V>, idx: u16, _hash_val: u64) {
+        self.list.push_front(line, idx);
+    }
+
+    #[inline(always)]
+    fn on_hit(&mut self, line: &mut DataLine<K, V>, idx: u16) {
+        line.get_ref(idx).mark_visited();
+    }
+
+    fn on_remove(&mut self, line: &mut DataLine<K, V>, idx: u16) {
+        if self.hand == idx {
+            self.hand = line.get_ref(idx).link.prev;
+        }
+        self.list.unlink(line, idx);
+    }
+
+    fn victim(&mut self, line: &mut DataLine<K, V>) -> u16 {
+        if self.list.is_empty() {
+            return NULL_IDX;
+        }
+        let mut idx = match self.hand {
+            hand if hand == NULL_IDX => self.list.tail,
+            hand => hand,
+        };
+        while line.get_ref(idx).clear_visited() {
+            idx = match line.get_ref(idx).link.prev {
+                prev if prev == NULL_IDX => self.list.tail,
+                prev => prev,
+            };
+        }
+        self.hand = idx;
+        idx
+    }
+}
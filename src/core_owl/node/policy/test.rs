@@ -0,0 +1,203 @@
+use super::super::data_line::{
+    data_line::DataLine,
+    data_line_impl::DataLineImpl,
+    entity::{Entity, Link},
+};
+use super::super::node::Node;
+use super::super::node_impl::NodeImpl;
+use super::super::array::ARR_SIZE;
+use super::super::NULL_IDX;
+use super::{Clock, EvictionPolicy, Fifo, GhostTable, Lru, Mru, Random, S3Fifo, Sieve};
+
+/// Builds a `DataLine` holding the entities `0..count` and registers them with `policy`.
+fn filled_line<P:EvictionPolicy<u16,u16>>(policy:&mut P,count:u16)->DataLine<u16,u16>{
+    let mut line = DataLine::new();
+    for idx in 0..count{
+        line.set(idx, Entity::new(idx, idx, Link::default(), Link::default()));
+        policy.on_insert(&mut line, idx, (idx as u64) << 32);
+    }
+    line
+}
+
+#[test]
+pub fn policy1_lru_mru_fifo_victims(){
+    let mut lru = Lru::new();
+    let mut line = filled_line(&mut lru, 4);
+    lru.on_hit(&mut line, 0);
+    assert_eq!(lru.victim(&mut line), 1);
+    lru.on_remove(&mut line, 1);
+    assert_eq!(lru.victim(&mut line), 2);
+
+    let mut mru = Mru::new();
+    let mut line = filled_line(&mut mru, 4);
+    assert_eq!(mru.victim(&mut line), 3);
+    mru.on_hit(&mut line, 1);
+    assert_eq!(mru.victim(&mut line), 1);
+
+    let mut fifo = Fifo::new();
+    let mut line = filled_line(&mut fifo, 4);
+    fifo.on_hit(&mut line, 0);
+    assert_eq!(fifo.victim(&mut line), 0);
+    for idx in 0..4{
+        fifo.on_remove(&mut line, idx);
+    }
+    assert_eq!(fifo.victim(&mut line), NULL_IDX);
+}
+
+#[test]
+pub fn policy2_random_victims(){
+    let mut random = Random::with_seed(7);
+    let mut line = filled_line(&mut random, 16);
+    for idx in (0..16).filter(|idx| idx % 2 == 0){
+        random.on_remove(&mut line, idx);
+    }
+    for _ in 0..100{
+        let victim = random.victim(&mut line);
+        assert!(victim % 2 == 1 && victim < 16);
+    }
+}
+
+#[test]
+pub fn policy3_node_fifo_eviction(){
+    let mut node = Node::<u64,u64,Fifo>::new();
+    for key in 0..ARR_SIZE as u64{
+        node.insert(key, key, key);
+    }
+    // A hit does not save the oldest key under FIFO.
+    assert_eq!(node.get(0, &0), Some(&0));
+    node.insert(100_000, 100_000, 0);
+    assert_eq!(node.get(0, &0), None);
+    assert_eq!(node.get(1, &1), Some(&1));
+    assert_eq!(node.len(), ARR_SIZE as usize);
+}
+
+#[test]
+pub fn policy4_sieve_and_clock_victims(){
+    let mut sieve = Sieve::new();
+    let mut line = filled_line(&mut sieve, 4);
+    sieve.on_hit(&mut line, 0);
+    sieve.on_hit(&mut line, 2);
+    // The hand skips the visited oldest entity without moving it.
+    assert_eq!(sieve.victim(&mut line), 1);
+    sieve.on_remove(&mut line, 1);
+    assert_eq!(sieve.victim(&mut line), 3);
+    sieve.on_remove(&mut line, 3);
+    // Flags were cleared by the first sweep, so the hand wraps to the tail.
+    assert_eq!(sieve.victim(&mut line), 0);
+
+    let mut clock = Clock::new();
+    let mut line = filled_line(&mut clock, 4);
+    clock.on_hit(&mut line, 0);
+    clock.on_hit(&mut line, 1);
+    assert_eq!(clock.victim(&mut line), 2);
+    clock.on_remove(&mut line, 2);
+    assert_eq!(clock.victim(&mut line), 3);
+    clock.on_remove(&mut line, 3);
+    assert_eq!(clock.victim(&mut line), 0);
+    clock.on_remove(&mut line, 0);
+    clock.on_remove(&mut line, 1);
+    assert_eq!(clock.victim(&mut line), NULL_IDX);
+}
+
+#[test]
+pub fn policy5_node_sieve_keeps_hot_keys(){
+    let mut node = Node::<u64,u64,Sieve>::new();
+    for key in 0..ARR_SIZE as u64{
+        node.insert(key, key, key);
+    }
+    for key in 0..10{
+        node.get(key, &key);
+    }
+    for key in 0..100{
+        node.insert(100_000 + key, 100_000 + key, key);
+    }
+    for key in 0..10{
+        assert_eq!(node.get(key, &key), Some(&key));
+    }
+    assert_eq!(node.get(10, &10), None);
+}
+
+#[test]
+pub fn policy6_ghost_table(){
+    let mut ghost = GhostTable::new(8);
+    for fingerprint in 1..=8{
+        ghost.insert(fingerprint);
+    }
+    assert!(ghost.remove(3));
+    assert!(!ghost.remove(3));
+    // Older ghosts fade out once `capacity` newer ones were inserted.
+    for fingerprint in 100..110{
+        ghost.insert(fingerprint);
+    }
+    assert!(!ghost.remove(1));
+    assert!(ghost.remove(109));
+}
+
+#[test]
+pub fn policy7_s3_fifo_promotion_and_ghosts(){
+    // A tiny small queue keeps the probation queue draining first on a 4 entity line.
+    let mut s3 = S3Fifo::with_small_ratio(0.0);
+    let mut line = filled_line(&mut s3, 4);
+    s3.on_hit(&mut line, 0);
+    // The oldest entity was hit on probation, so it is promoted and 1 goes instead.
+    assert_eq!(s3.victim(&mut line), 1);
+    s3.on_evict(&mut line, 1);
+
+    // Coming back while still a ghost lands straight in the main queue.
+    s3.on_insert(&mut line, 1, 1 << 32);
+    for idx in [2, 3]{
+        assert_eq!(s3.victim(&mut line), idx);
+        s3.on_evict(&mut line, idx);
+    }
+    // Main queue evicts in FIFO order: the promoted entity, then the former ghost.
+    assert_eq!(s3.victim(&mut line), 0);
+    s3.on_evict(&mut line, 0);
+    assert_eq!(s3.victim(&mut line), 1);
+}
+
+#[test]
+pub fn policy8_default_sieve_evicts_before_full(){
+    use std::sync::Arc;
+    use super::super::weigher::Budget;
+
+    // The budget evicts long before every slot is taken, so the sweep must start
+    // from the tail of the list rather than from an unused slot.
+    let budget = Arc::new(Budget::new(2));
+    let mut node = Node::<u64,String,Sieve>::default().with_weigher(|_: &u64, _: &String| 1, budget);
+    for key in 0..10u64{
+        node.insert(key, key, key.to_string());
+    }
+    assert_eq!(node.len(), 2);
+    assert_eq!(node.get(9, &9), Some(&String::from("9")));
+    assert_eq!(node.get(0, &0), None);
+}
+
+#[test]
+pub fn policy9_s3_fifo_ghosts_only_evicted_entities(){
+    use std::sync::Arc;
+    use super::super::admission::TinyLfu;
+    use super::super::weigher::Budget;
+
+    // With most of the node as its small queue, every entity stays on probation
+    // until it is evicted, or comes back as a ghost.
+    let budget = Arc::new(Budget::new(3));
+    let mut node = Node::<u64,u64,S3Fifo>::with_policy(S3Fifo::with_small_ratio(0.9))
+        .with_admission(TinyLfu::new())
+        .with_weigher(|_: &u64, _: &u64| 1, budget);
+    // Hashes differ in their upper half, which the ghost fingerprints keep.
+    let hash = |key: u64| (key + 1) << 32;
+    for key in 0..3u64{
+        node.insert(hash(key), key, key);
+    }
+    // A key seen as often as the victim is turned away, and 0 stays resident
+    // without becoming a ghost.
+    assert_eq!(node.try_insert(hash(3), 3, 3, None), Err((3, 3)));
+    node.delete(hash(0), &0);
+    node.insert(hash(0), 0, 0);
+
+    // So it is back on probation, behind 1 and 2, rather than promoted.
+    assert_eq!(node.try_insert(hash(4), 4, 4, None), Err((4, 4)));
+    assert_eq!(node.try_insert(hash(4), 4, 4, None), Ok(None));
+    assert_eq!(node.get(hash(1), &1), None);
+    assert_eq!(node.get(hash(0), &0), Some(&0));
+}
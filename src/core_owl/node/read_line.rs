@@ -0,0 +1,248 @@
+use std::borrow::Borrow;
+use std::marker::PhantomData;
+use std::ptr::null_mut;
+use std::sync::atomic::{
+    fence, AtomicPtr, AtomicU32, AtomicU64,
+    Ordering::{Acquire, Relaxed, Release},
+};
+
+use super::array::ARR_SIZE;
+use super::epoch::{Collector, Pin};
+use super::handle::Handle;
+use super::NULL_IDX;
+
+/// Number of recent lock-free hits remembered until the next write.
+const HIT_SLOTS: usize = 128;
+
+/// Hit slot holding no hit.
+const NO_HIT: u64 = u64::MAX;
+
+/// A key-value pair published to lock-free readers.
+pub struct Record<K, V> {
+    /// Key of the entry.
+    pub key: K,
+
+    /// Value of the entry.
+    pub value: V,
+
+    /// `HashLine` bucket whose chain holds the entry.
+    bucket: u16,
+
+    /// Generation of the slot holding the entry.
+    gen: u32,
+}
+
+/// `ReadLine` mirrors the chains of a `Node` for readers that take no lock.
+///
+/// Every bucket head, every `chain.next` link and every entry of the node is
+/// copied into atomics, the links as packed `Handle`s and the entries as heap
+/// allocated `Record`s. Only the writer holding the node changes them, while
+/// readers walk them concurrently:
+/// - Every slot has a version counter, odd while the writer changes the slot.
+///   A reader reads the record and next link of a slot between two loads of its
+///   version, and starts over from the bucket head if they differ.
+/// - Every link carries the generation of the slot it leads to. A reader landing
+///   on a slot that was freed and reused since the link was read finds a record
+///   of another generation, and starts over instead of following a foreign chain.
+/// - Unlinked or replaced records are retired to an epoch `Collector` instead of
+///   being dropped, so a pinned reader never sees a freed record.
+///
+/// Lock-free hits cannot touch the eviction policy, so they are remembered in a
+/// small lossy buffer that the writer replays on its next write.
+pub struct ReadLine<K, V> {
+    /// Handle of the first slot of every bucket's chain.
+    heads: Box<[AtomicU64]>,
+
+    /// Handle of the next slot of every slot's chain.
+    next: Box<[AtomicU64]>,
+
+    /// Version of every slot, odd while the writer changes it.
+    versions: Box<[AtomicU32]>,
+
+    /// Entry of every slot, null for free slots.
+    records: Box<[AtomicPtr<Record<K, V>>]>,
+
+    /// Handles of the recently hit slots.
+    hits: Box<[AtomicU64]>,
+
+    /// Drops retired records once no reader can reference them.
+    collector: Collector<Record<K, V>>,
+
+    /// Copies the key and value of a node entry into a record.
+    copy: fn(&K, &V) -> (K, V),
+
+    /// Makes the line `Send` and `Sync` only when records are.
+    records_marker: PhantomData<Box<Record<K, V>>>,
+}
+
+impl<K, V> ReadLine<K, V> {
+    /// Creates a line mirroring an empty node.
+    ///
+    /// # Arguments
+    /// * `copy` - Copies the key and value of a node entry into a record.
+    pub fn new(copy: fn(&K, &V) -> (K, V)) -> Self {
+        let line = || (0..ARR_SIZE).map(|_| AtomicU64::new(Handle::NULL.to_bits())).collect();
+        ReadLine {
+            heads: line(),
+            next: line(),
+            versions: (0..ARR_SIZE).map(|_| AtomicU32::new(0)).collect(),
+            records: (0..ARR_SIZE).map(|_| AtomicPtr::new(null_mut())).collect(),
+            hits: (0..HIT_SLOTS).map(|_| AtomicU64::new(NO_HIT)).collect(),
+            collector: Collector::new(),
+            copy,
+            records_marker: PhantomData,
+        }
+    }
+
+    /// Marks the slot at `idx` as changing, runs `change` and marks it stable again.
+    #[inline(always)]
+    fn change<T>(&self, idx: u16, change: impl FnOnce() -> T) -> T {
+        let version = &self.versions[idx as usize];
+        version.fetch_add(1, Acquire);
+        // Readers that see any of the changes also see the odd version.
+        fence(Release);
+        let result = change();
+        version.fetch_add(1, Release);
+        result
+    }
+
+    /// Publishes the entry stored at `handle` as the new head of `bucket`.
+    ///
+    /// # Arguments
+    /// * `bucket` - The `HashLine` bucket of the entry.
+    /// * `handle` - The slot of the entry, unlinked until now, in its current generation.
+    /// * `key`, `value` - The entry, copied into a record.
+    pub fn link(&self, bucket: usize, handle: Handle, key: &K, value: &V) {
+        let (key, value) = (self.copy)(key, value);
+        let record = Box::into_raw(Box::new(Record { key, value, bucket: bucket as u16, gen: handle.gen }));
+        let idx = handle.idx as usize;
+        self.change(handle.idx, || {
+            self.next[idx].store(self.heads[bucket].load(Relaxed), Release);
+            self.records[idx].store(record, Release);
+        });
+        self.heads[bucket].store(handle.to_bits(), Release);
+    }
+
+    /// Publishes a new value for the entry at `idx`, retiring the previous record.
+    pub fn replace(&self, idx: u16, key: &K, value: &V) {
+        let (key, value) = (self.copy)(key, value);
+        let previous = self.records[idx as usize].load(Relaxed);
+        let (bucket, gen) = unsafe { ((*previous).bucket, (*previous).gen) };
+        let record = Box::into_raw(Box::new(Record { key, value, bucket, gen }));
+        self.change(idx, || self.records[idx as usize].store(record, Release));
+        self.collector.retire(unsafe { Box::from_raw(previous) });
+    }
+
+    /// Removes the entry at `idx` from the chain of its bucket, retiring its record.
+    ///
+    /// # Arguments
+    /// * `prev` - The slot before `idx` in the chain, `NULL_IDX` if `idx` is the head.
+    pub fn unlink(&self, idx: u16, prev: u16) {
+        let record = self.change(idx, || {
+            let record = self.records[idx as usize].swap(null_mut(), Relaxed);
+            let next = self.next[idx as usize].load(Relaxed);
+            match prev == NULL_IDX {
+                true => self.heads[unsafe { (*record).bucket } as usize].store(next, Release),
+                false => self.next[prev as usize].store(next, Release),
+            }
+            record
+        });
+        // The link of `idx` itself is left in place, so readers standing on it
+        // carry on with the rest of the chain.
+        self.collector.retire(unsafe { Box::from_raw(record) });
+    }
+
+    /// Pins the current epoch, keeping every record reachable from now on alive
+    /// until the `Pin` is dropped.
+    #[inline(always)]
+    pub fn pin(&self) -> Pin<'_, Record<K, V>> {
+        self.collector.pin()
+    }
+
+    /// Walks the chain of `bucket` looking for `key`, or a borrowed form of it,
+    /// without taking any lock.
+    ///
+    /// # Returns
+    /// The record of `key`, valid as long as `pin` is, with the handle of its slot.
+    pub fn find<'a, Q>(&'a self, _pin: &'a Pin<'_, Record<K, V>>, bucket: usize, key: &Q) -> Option<(&'a Record<K, V>, Handle)>
+    where
+        K: Borrow<Q>,
+        Q: Eq + ?Sized,
+    {
+        'walk: loop {
+            let mut link = Handle::from_bits(self.heads[bucket].load(Acquire));
+            while !link.is_null() {
+                let idx = link.idx;
+                let version = self.versions[idx as usize].load(Acquire);
+                if version & 1 == 1 {
+                    continue 'walk;
+                }
+                let record = self.records[idx as usize].load(Acquire);
+                let next = self.next[idx as usize].load(Acquire);
+                fence(Acquire);
+                if self.versions[idx as usize].load(Relaxed) != version {
+                    continue 'walk;
+                }
+                // A null record is an unlinked slot, whose link still leads to the
+                // rest of the chain as it was when the slot was unlinked.
+                if let Some(record) = unsafe { record.as_ref() } {
+                    if record.gen != link.gen {
+                        continue 'walk;
+                    }
+                    if record.key.borrow() == key {
+                        return Some((record, link));
+                    }
+                }
+                link = Handle::from_bits(next);
+            }
+            return None;
+        }
+    }
+
+    /// Remembers a lock-free hit of the slot of `handle`, overwriting an older
+    /// hit if the buffer is full.
+    #[inline(always)]
+    pub fn hit(&self, handle: Handle) {
+        let slot = (handle.idx as usize ^ handle.gen as usize) % HIT_SLOTS;
+        self.hits[slot].store(handle.to_bits(), Relaxed);
+    }
+
+    /// Hands the handle of every remembered hit to `on_hit`, which must check
+    /// that it is not stale.
+    pub fn drain_hits(&self, mut on_hit: impl FnMut(Handle)) {
+        for hit in self.hits.iter() {
+            if hit.load(Relaxed) == NO_HIT {
+                continue;
+            }
+            match hit.swap(NO_HIT, Relaxed) {
+                NO_HIT => {}
+                hit => on_hit(Handle::from_bits(hit)),
+            }
+        }
+    }
+
+    /// Drops the retired records no reader can reference anymore.
+    ///
+    /// # Returns
+    /// The number of dropped records.
+    pub fn collect(&self) -> usize {
+        self.collector.collect()
+    }
+
+    /// Returns the number of retired records not dropped yet.
+    pub fn pending(&self) -> usize {
+        self.collector.pending()
+    }
+}
+
+impl<K, V> Drop for ReadLine<K, V> {
+    /// Drops the records still published.
+    fn drop(&mut self) {
+        for record in self.records.iter_mut() {
+            let record = *record.get_mut();
+            if !record.is_null() {
+                drop(unsafe { Box::from_raw(record) });
+            }
+        }
+    }
+}
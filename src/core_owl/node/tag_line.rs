@@ -0,0 +1,187 @@
+use std::simd::{cmp::SimdPartialEq, cmp::SimdPartialOrd, Simd};
+
+use super::array::ARR_SIZE;
+
+/// Control bytes compared at once.
+const GROUP: usize = 16;
+
+/// Number of groups, so the table never gets more than half full.
+const GROUPS: usize = 2 * (ARR_SIZE as usize).next_power_of_two() / GROUP;
+
+/// Number of positions of the table.
+const CAPACITY: usize = GROUPS * GROUP;
+
+/// Control byte of a position that never held anything since the last rebuild.
+const EMPTY: u8 = 0x80;
+
+/// Control byte of a position whose entry was removed.
+const DELETED: u8 = 0xFE;
+
+/// Position of a slot that is not in the table.
+const NO_POS: u32 = u32::MAX;
+
+/// A group of control bytes.
+type Group = Simd<u8, GROUP>;
+
+/// `TagLine` is an alternative to the chained `HashLine`, laid out like a SwissTable.
+///
+/// Every slot of the `DataLine` is indexed at a position of an open addressed
+/// table. Positions are grouped by 16, and every position has a control byte
+/// holding 7 bits of the slot's hash, or `EMPTY` or `DELETED`. A lookup
+/// compares the whole group with the hash's tag in one SIMD comparison, and only
+/// touches the entities whose tag matches, usually one. Groups are probed
+/// quadratically from the hash's home group until one holds an `EMPTY` byte.
+///
+/// Removals leave `DELETED` bytes in groups without an `EMPTY` byte, since a probe
+/// may have passed through them; once there are too many, the table is rebuilt.
+pub struct TagLine {
+    /// Control byte of every position.
+    ctrl: Box<[u8]>,
+
+    /// Slot indexed at every position.
+    slots: Box<[u16]>,
+
+    /// Home group of the slot indexed at every position, to rebuild the table.
+    homes: Box<[u16]>,
+
+    /// Position of every slot, `NO_POS` if the slot is not indexed.
+    positions: Box<[u32]>,
+
+    /// Number of `DELETED` control bytes.
+    deleted: usize,
+}
+
+impl TagLine {
+    /// Creates a table indexing no slot.
+    pub fn new() -> Self {
+        TagLine {
+            ctrl: vec![EMPTY; CAPACITY].into_boxed_slice(),
+            slots: vec![0; CAPACITY].into_boxed_slice(),
+            homes: vec![0; CAPACITY].into_boxed_slice(),
+            positions: vec![NO_POS; ARR_SIZE as usize].into_boxed_slice(),
+            deleted: 0,
+        }
+    }
+
+    /// Returns the home group of `hash_val`.
+    #[inline(always)]
+    const fn home(hash_val: u64) -> usize {
+        (hash_val as usize) % GROUPS
+    }
+
+    /// Returns the 7 bit tag of `hash_val`, taken from bits the home group does not
+    /// use. The top 16 bits are avoided too: they are the hash's position on the
+    /// `Ring`, so keys of one node share most of them.
+    #[inline(always)]
+    const fn tag(hash_val: u64) -> u8 {
+        (hash_val >> 41) as u8 & 0x7F
+    }
+
+    /// Loads the control bytes of `group`.
+    #[inline(always)]
+    fn group(&self, group: usize) -> Group {
+        Group::from_slice(&self.ctrl[group * GROUP..(group + 1) * GROUP])
+    }
+
+    /// Returns the group probed `probe` steps after `home`. Triangular steps
+    /// visit every group of a power of two table.
+    #[inline(always)]
+    const fn probe(home: usize, probe: usize) -> usize {
+        (home + probe * (probe + 1) / 2) % GROUPS
+    }
+
+    /// Looks up the slot of an entry of hash `hash_val`.
+    ///
+    /// # Arguments
+    /// * `hash_val` - The full hash of the key.
+    /// * `is_match` - Tells whether the entity in a candidate slot has the key.
+    ///
+    /// # Returns
+    /// The slot for which `is_match` returned `true`, if any.
+    #[inline(always)]
+    pub fn find(&self, hash_val: u64, mut is_match: impl FnMut(u16) -> bool) -> Option<u16> {
+        let (home, tag) = (Self::home(hash_val), Group::splat(Self::tag(hash_val)));
+        for probe in 0..GROUPS {
+            let group = Self::probe(home, probe);
+            let ctrl = self.group(group);
+            let mut matches = ctrl.simd_eq(tag).to_bitmask();
+            while matches != 0 {
+                let slot = self.slots[group * GROUP + matches.trailing_zeros() as usize];
+                if is_match(slot) {
+                    return Some(slot);
+                }
+                matches &= matches - 1;
+            }
+            if ctrl.simd_eq(Group::splat(EMPTY)).any() {
+                return None;
+            }
+        }
+        None
+    }
+
+    /// Indexes the slot at `idx`, which must not be indexed already, under `hash_val`.
+    pub fn insert(&mut self, hash_val: u64, idx: u16) {
+        self.place(Self::home(hash_val), Self::tag(hash_val), idx);
+    }
+
+    /// Puts `idx` at the first free position probed from `home`.
+    fn place(&mut self, home: usize, tag: u8, idx: u16) {
+        for probe in 0..GROUPS {
+            let group = Self::probe(home, probe);
+            // `EMPTY` and `DELETED` are the only control bytes with the top bit set.
+            let free = self.group(group).simd_ge(Group::splat(EMPTY)).to_bitmask();
+            if free != 0 {
+                let pos = group * GROUP + free.trailing_zeros() as usize;
+                if self.ctrl[pos] == DELETED {
+                    self.deleted -= 1;
+                }
+                self.ctrl[pos] = tag;
+                self.slots[pos] = idx;
+                self.homes[pos] = home as u16;
+                self.positions[idx as usize] = pos as u32;
+                return;
+            }
+        }
+        unreachable!("the tag line holds twice as many positions as slots");
+    }
+
+    /// Stops indexing the slot at `idx`, if it is indexed.
+    pub fn remove(&mut self, idx: u16) {
+        let pos = std::mem::replace(&mut self.positions[idx as usize], NO_POS);
+        if pos == NO_POS {
+            return;
+        }
+        let pos = pos as usize;
+        // Probes stop at a group holding an `EMPTY` byte, so none of them ever went
+        // past this group and the position can become `EMPTY` again.
+        match self.group(pos / GROUP).simd_eq(Group::splat(EMPTY)).any() {
+            true => self.ctrl[pos] = EMPTY,
+            false => {
+                self.ctrl[pos] = DELETED;
+                self.deleted += 1;
+                if self.deleted > CAPACITY / 8 {
+                    self.rebuild();
+                }
+            }
+        }
+    }
+
+    /// Reindexes every slot from scratch, clearing all `DELETED` bytes.
+    fn rebuild(&mut self) {
+        let live: Vec<(usize, u8, u16)> = (0..CAPACITY)
+            .filter(|&pos| self.ctrl[pos] < EMPTY)
+            .map(|pos| (self.homes[pos] as usize, self.ctrl[pos], self.slots[pos]))
+            .collect();
+        self.ctrl.fill(EMPTY);
+        self.deleted = 0;
+        for (home, tag, idx) in live {
+            self.place(home, tag, idx);
+        }
+    }
+}
+
+impl Default for TagLine {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -22,4 +22,737 @@ pub fn empty_line2_idx_return(){
         let len = e_list.get_empty_count();
         assert_eq!(len ,idx + 1);
     }
-}
\ No newline at end of file
+}
+
+#[test]
+pub fn empty_line3_idx_reuse(){
+    let mut e_list = super::empty_line::EmptyMap::new();
+    for _ in 0..65521{
+        e_list.get_empty_idx();
+    }
+    // Free slots from fully occupied words and make sure they are handed out again.
+    for idx in [0, 64, 4096, 65520]{
+        e_list.return_free_idx(idx);
+    }
+    let mut reused = [0u16;4].map(|_| e_list.get_empty_idx());
+    reused.sort();
+    assert_eq!(reused, [0, 64, 4096, 65520]);
+    assert_eq!(e_list.get_empty_count(), 0);
+}
+
+#[test]
+pub fn node1_insert_get_delete(){
+    use super::node::Node;
+    use super::node_impl::NodeImpl;
+
+    let mut node = Node::<u64,u64>::new();
+    for key in 0..10000u64{
+        assert_eq!(node.insert(key, key, key * 2), None);
+    }
+    assert_eq!(node.len(), 10000);
+    assert_eq!(node.insert(7, 7, 70), Some(14));
+    assert_eq!(node.get(7, &7), Some(&70));
+    assert_eq!(node.delete(7, &7), Some((7, 70)));
+    assert_eq!(node.get(7, &7), None);
+    assert_eq!(node.delete(7, &7), None);
+    for key in (0..10000u64).filter(|key| *key != 7){
+        assert_eq!(node.get(key, &key), Some(&(key * 2)));
+    }
+    assert_eq!(node.len(), 9999);
+}
+
+#[test]
+pub fn node2_collision_chain(){
+    use super::node::Node;
+    use super::node_impl::NodeImpl;
+
+    // Every key shares the same hash, so they all land on one chain.
+    let mut node = Node::<u32,u32>::new();
+    for key in 0..5{
+        node.insert(42, key, key);
+    }
+    // Remove from the middle, the head and the tail of the chain.
+    assert_eq!(node.delete(42, &2), Some((2, 2)));
+    assert_eq!(node.delete(42, &4), Some((4, 4)));
+    assert_eq!(node.delete(42, &0), Some((0, 0)));
+    assert_eq!(node.get(42, &1), Some(&1));
+    assert_eq!(node.get(42, &3), Some(&3));
+    assert_eq!(node.get(42, &2), None);
+
+    node.insert(42, 5, 5);
+    assert_eq!(node.get(42, &5), Some(&5));
+    assert_eq!(node.delete(42, &1), Some((1, 1)));
+    assert_eq!(node.delete(42, &3), Some((3, 3)));
+    assert_eq!(node.delete(42, &5), Some((5, 5)));
+    assert!(node.is_empty());
+}
+
+#[test]
+pub fn node3_lru_eviction(){
+    use super::array::ARR_SIZE;
+    use super::node::Node;
+    use super::node_impl::NodeImpl;
+
+    let mut node = Node::<u64,u64>::new();
+    for key in 0..ARR_SIZE as u64{
+        node.insert(key, key, key);
+    }
+    // Touch the two oldest keys so that key 2 becomes the least recently used.
+    assert_eq!(node.get(0, &0), Some(&0));
+    node.insert(1, 1, 10);
+
+    node.insert(100_000, 100_000, 1);
+    assert_eq!(node.len(), ARR_SIZE as usize);
+    assert_eq!(node.get(2, &2), None);
+    assert_eq!(node.get(0, &0), Some(&0));
+    assert_eq!(node.get(1, &1), Some(&10));
+    assert_eq!(node.get(100_000, &100_000), Some(&1));
+
+    // Evictions keep following the recency order, including across shared buckets.
+    node.insert(3 + ARR_SIZE as u64, 0, 0);
+    assert_eq!(node.get(3, &3), None);
+    assert_eq!(node.get(4, &4), Some(&4));
+    assert_eq!(node.get(3 + ARR_SIZE as u64, &0), Some(&0));
+}
+
+#[test]
+pub fn node4_ttl_expiry(){
+    use std::time::Duration;
+    use super::node::Node;
+    use super::node_impl::NodeImpl;
+
+    let mut node = Node::<u64,u64>::new();
+    node.insert_with_ttl(1, 1, 1, Duration::from_millis(20));
+    node.insert_with_ttl(2, 2, 2, Duration::from_secs(60));
+    node.insert(3, 3, 3);
+    assert_eq!(node.get(1, &1), Some(&1));
+    assert!(node.ttl(2, &2).unwrap() > Duration::from_secs(59));
+    assert_eq!(node.ttl(3, &3), None);
+
+    std::thread::sleep(Duration::from_millis(30));
+    // Lazy expiry on lookup.
+    assert_eq!(node.get(1, &1), None);
+    assert_eq!(node.len(), 2);
+
+    // Overwriting without a TTL removes the deadline.
+    node.insert_with_ttl(4, 4, 4, Duration::from_millis(10));
+    node.insert(2, 2, 20);
+    assert_eq!(node.ttl(2, &2), None);
+
+    // The timer wheel ticks once per second.
+    std::thread::sleep(Duration::from_millis(1100));
+    // Active expiry reclaims the slot without a lookup.
+    assert_eq!(node.purge_expired(), 1);
+    assert_eq!(node.len(), 2);
+    assert_eq!(node.get(2, &2), Some(&20));
+    assert_eq!(node.get(3, &3), Some(&3));
+}
+
+#[test]
+pub fn timer_wheel1_cascade(){
+    use super::timer_wheel::TimerWheel;
+
+    let mut deadlines = vec![0u64; super::array::ARR_SIZE as usize];
+    let mut wheel = TimerWheel::new();
+    // Seconds, minutes, hours and overflow levels, plus a cancelled slot.
+    for (idx, deadline) in [(0u16, 500u64), (1, 90_500), (2, 7_200_000), (3, 90_000_000), (4, 5_000)] {
+        deadlines[idx as usize] = deadline;
+        wheel.schedule(idx, deadline);
+    }
+    wheel.cancel(4);
+
+    let mut expired = Vec::new();
+    wheel.advance(1_000, &deadlines, &mut expired);
+    assert_eq!(expired, [0]);
+    wheel.advance(90_999, &deadlines, &mut expired);
+    assert_eq!(expired, [0]);
+    wheel.advance(91_000, &deadlines, &mut expired);
+    assert_eq!(expired, [0, 1]);
+    wheel.advance(7_199_999, &deadlines, &mut expired);
+    assert_eq!(expired, [0, 1]);
+    wheel.advance(7_200_000, &deadlines, &mut expired);
+    assert_eq!(expired, [0, 1, 2]);
+    wheel.advance(100_000_000, &deadlines, &mut expired);
+    assert_eq!(expired, [0, 1, 2, 3]);
+
+    // A deadline already in the past fires on the next tick.
+    deadlines[5] = 1;
+    wheel.schedule(5, 1);
+    wheel.advance(100_001_000, &deadlines, &mut expired);
+    assert_eq!(expired, [0, 1, 2, 3, 5]);
+}
+
+#[test]
+pub fn empty_line4_batch_return(){
+    let mut e_list = super::empty_line::EmptyMap::new();
+    for _ in 0..65521{
+        e_list.get_empty_idx();
+    }
+    let idxs: Vec<u16> = (100..5000).collect();
+    e_list.return_free_idxs(&idxs);
+    assert_eq!(e_list.get_empty_count(), 4900);
+    for _ in 0..4900{
+        let idx = e_list.get_empty_idx();
+        assert!((100..5000).contains(&idx));
+    }
+    assert_eq!(e_list.get_empty_idx(), super::NULL_IDX);
+}
+
+#[test]
+pub fn node5_weighted_budget(){
+    use std::sync::Arc;
+    use super::node::Node;
+    use super::node_impl::NodeImpl;
+    use super::weigher::Budget;
+
+    let weigher = |_: &u64, value: &Vec<u8>| value.len() as u32;
+    let budget = Arc::new(Budget::new(1000));
+    let mut node = Node::<u64,Vec<u8>>::new().with_weigher(weigher, budget.clone());
+    for key in 0..10u64{
+        node.insert(key, key, vec![0; 100]);
+    }
+    assert_eq!((node.len(), budget.used()), (10, 1000));
+
+    // A heavy entry evicts as many least recently used entries as needed.
+    node.get(0, &0);
+    node.insert(10, 10, vec![0; 300]);
+    assert_eq!((node.len(), node.weighted_size()), (8, 1000));
+    assert!(node.get(0, &0).is_some());
+    for key in 1..4u64{
+        assert_eq!(node.get(key, &key), None);
+    }
+
+    // Growing an existing entry evicts others, shrinking it refunds the budget.
+    assert_eq!(node.insert(0, 0, vec![0; 200]).map(|v| v.len()), Some(100));
+    assert_eq!((node.len(), budget.used()), (7, 1000));
+    node.insert(0, 0, vec![0; 50]);
+    assert_eq!(budget.used(), 850);
+
+    // An entry heavier than the whole budget is never stored.
+    assert_eq!(node.insert(11, 11, vec![0; 1001]), None);
+    assert_eq!(node.get(11, &11), None);
+    assert_eq!(budget.used(), 850);
+
+    // A budget shared by two nodes bounds their sum, each node evicting its own
+    // entries, but the spent budget does not starve a node below its share of 500.
+    let mut other = Node::<u64,Vec<u8>>::new().with_weigher(weigher, budget.clone());
+    other.insert(20, 20, vec![0; 150]);
+    assert_eq!(budget.used(), 1000);
+    other.insert(21, 21, vec![0; 100]);
+    assert_eq!((other.len(), other.weighted_size(), budget.used()), (2, 250, 1100));
+    other.insert(22, 22, vec![0; 400]);
+    assert_eq!((other.len(), other.weighted_size()), (2, 500));
+    assert_eq!(other.get(20, &20), None);
+
+    // The node above its share evicts back under the limit on its next insert.
+    node.insert(12, 12, vec![0; 50]);
+    assert!(budget.used() <= 1000);
+    drop(node);
+    assert_eq!(budget.used(), 500);
+}
+
+#[test]
+pub fn node6_lock_free_reads(){
+    use std::sync::atomic::{AtomicUsize, Ordering::Relaxed};
+    use std::sync::Arc;
+    use std::thread;
+    use xxhash_rust::xxh3::xxh3_64;
+    use super::concurrent_node::ConcurrentNode;
+
+    static DROPPED: AtomicUsize = AtomicUsize::new(0);
+    #[derive(Default)]
+    struct Tracked(u64);
+    impl Drop for Tracked{
+        fn drop(&mut self){ DROPPED.fetch_add(1, Relaxed); }
+    }
+
+    let hash = |key: u64| xxh3_64(&key.to_le_bytes());
+    let node = ConcurrentNode::<u64,Tracked>::new();
+    node.insert(hash(1), 1, Tracked(10));
+    let value = node.get(hash(1), &1).unwrap();
+    assert_eq!(value.0, 10);
+
+    // The overwritten value outlives the write while a reader holds it.
+    assert_eq!(node.insert(hash(1), 1, Tracked(11)).map(|previous| previous.0), Some(10));
+    node.insert(hash(2), 2, Tracked(20));
+    assert_eq!(value.0, 10);
+    assert!(node.pending_drops() > 0);
+    assert_eq!(DROPPED.load(Relaxed), 0);
+    drop(value);
+    node.insert(hash(3), 3, Tracked(30));
+    node.remove(hash(3), &3);
+    node.insert(hash(3), 3, Tracked(30));
+    assert_eq!(DROPPED.load(Relaxed), 2);
+    assert_eq!(node.get(hash(1), &1).map(|value| value.0), Some(11));
+
+    // Readers keep finding consistent values while a writer churns the same keys.
+    let node = Arc::new(ConcurrentNode::<u64,String>::new());
+    for key in 0..1000u64{
+        node.insert(hash(key), key, key.to_string());
+    }
+    let readers: Vec<_> = (0..4).map(|_| {
+        let node = Arc::clone(&node);
+        thread::spawn(move || {
+            for round in 0..50_000u64{
+                let key = round % 1000;
+                if let Some(value) = node.get(hash(key), &key){
+                    assert_eq!(value.split('-').next().unwrap(), key.to_string());
+                }
+            }
+        })
+    }).collect();
+    for round in 0..20u64{
+        for key in 0..1000u64{
+            match (key + round) % 3{
+                0 => { node.remove(hash(key), &key); }
+                _ => { node.insert(hash(key), key, format!("{key}-{round}")); }
+            }
+        }
+    }
+    for reader in readers{
+        reader.join().unwrap();
+    }
+}
+
+#[test]
+pub fn node7_generational_handles(){
+    use xxhash_rust::xxh3::xxh3_64;
+    use super::concurrent_node::ConcurrentNode;
+    use super::node::Node;
+    use super::node_impl::NodeImpl;
+
+    let hash = |key: u64| xxh3_64(&key.to_le_bytes());
+    let mut node = Node::<u64,u64>::new();
+    for key in 0..65521u64{
+        node.insert(hash(key), key, key);
+    }
+    let stale = node.handle(hash(7), &7).unwrap();
+    assert_eq!(node.get_by_handle(stale), Some(&7));
+
+    // The node is full, so the freed slot is the one the next key takes.
+    node.delete(hash(7), &7);
+    assert!(!node.is_live(stale));
+    node.insert(hash(100_000), 100_000, 1);
+    let fresh = node.handle(hash(100_000), &100_000).unwrap();
+    assert_eq!(fresh.idx, stale.idx);
+    assert_ne!(fresh.gen, stale.gen);
+    assert_eq!(node.get_by_handle(stale), None);
+    assert_eq!(node.remove_by_handle(stale), None);
+    assert_eq!(node.remove_by_handle(fresh), Some((100_000, 1)));
+    assert!(!node.is_live(fresh));
+
+    let node = ConcurrentNode::<u64,u64>::new();
+    node.insert(hash(1), 1, 10);
+    let handle = node.get(hash(1), &1).unwrap().handle();
+    assert!(node.is_live(handle));
+    node.remove(hash(1), &1);
+    node.insert(hash(1), 1, 11);
+    assert!(!node.is_live(handle));
+    assert_ne!(node.get(hash(1), &1).unwrap().handle(), handle);
+}
+
+#[test]
+pub fn node8_tag_line(){
+    use xxhash_rust::xxh3::xxh3_64;
+    use super::node::Node;
+    use super::node_impl::NodeImpl;
+
+    let hash = |key: u64| xxh3_64(&key.to_le_bytes());
+    let mut node = Node::<u64,u64>::new().with_tag_line();
+    // Twice the capacity, so half the keys are evicted through the tag line.
+    for key in 0..131_042u64{
+        node.insert(hash(key), key, key);
+    }
+    assert_eq!(node.len(), 65521);
+    for key in 0..65521u64{
+        assert_eq!(node.get(hash(key), &key), None);
+    }
+    for key in 65521..131_042u64{
+        assert_eq!(node.get(hash(key), &key), Some(&key));
+    }
+
+    // Churn deletes and reinserts, reusing positions freed in the table.
+    for round in 0..4u64{
+        for key in 65521..131_042u64{
+            if key % 4 == round{
+                assert_eq!(node.delete(hash(key), &key), Some((key, key)));
+                node.insert(hash(key + 1_000_000), key + 1_000_000, key);
+            }
+        }
+    }
+    for key in 65521..131_042u64{
+        assert_eq!(node.get(hash(key), &key), None);
+        assert_eq!(node.get(hash(key + 1_000_000), &(key + 1_000_000)), Some(&key));
+    }
+
+    // Filling whole groups makes removals leave `DELETED` bytes, until the table
+    // is rebuilt.
+    let mut tags = super::tag_line::TagLine::new();
+    let tag_hash = |idx: u16| (idx as u64 / 16) | (idx as u64 % 128) << 41;
+    for idx in 0..65521u16{
+        tags.insert(tag_hash(idx), idx);
+    }
+    for idx in (0..65521u16).filter(|idx| idx % 3 != 0){
+        tags.remove(idx);
+    }
+    for idx in 0..65521u16{
+        let found = tags.find(tag_hash(idx), |slot| slot == idx);
+        assert_eq!(found.is_some(), idx % 3 == 0);
+    }
+}
+
+#[test]
+pub fn empty_line5_occupied(){
+    let mut e_list = super::empty_line::EmptyMap::new();
+    assert_eq!(e_list.occupied().count(), 0);
+    for _ in 0..65521{
+        e_list.get_empty_idx();
+    }
+    assert!(e_list.occupied().eq(0..65521));
+    for idx in (0..65521).filter(|idx| idx % 3 != 0){
+        e_list.return_free_idx(idx);
+    }
+    assert!(e_list.occupied().eq((0..65521).filter(|idx| idx % 3 == 0)));
+}
+
+#[test]
+pub fn node9_full_hash(){
+    use std::cell::Cell;
+    use std::hash::{Hash, Hasher};
+    use super::array::ARR_SIZE;
+    use super::node::Node;
+    use super::node_impl::NodeImpl;
+
+    thread_local!{
+        static COMPARISONS: Cell<usize> = const { Cell::new(0) };
+    }
+
+    /// A key counting how many times it is compared.
+    #[derive(Debug, Clone, Copy)]
+    struct Key(u64);
+
+    impl PartialEq for Key{
+        fn eq(&self, other: &Self) -> bool{
+            COMPARISONS.with(|count| count.set(count.get() + 1));
+            self.0 == other.0
+        }
+    }
+
+    impl Eq for Key{}
+
+    impl Hash for Key{
+        fn hash<H: Hasher>(&self, state: &mut H){
+            self.0.hash(state);
+        }
+    }
+
+    // Every hash lands in the same bucket, so the keys share one chain.
+    let hash = |key: u64| key * ARR_SIZE as u64;
+    let mut node = Node::<Key,u64>::new();
+    for key in 0..8u64{
+        node.insert(hash(key), Key(key), key);
+    }
+    COMPARISONS.with(|count| count.set(0));
+    for key in 0..8u64{
+        assert_eq!(node.get(hash(key), &Key(key)), Some(&key));
+    }
+    // Stored hashes leave a single key comparison per lookup, the chain needs more.
+    let comparisons = COMPARISONS.with(|count| count.get());
+    match cfg!(feature = "full-hash"){
+        true => assert_eq!(comparisons, 8),
+        false => assert!(comparisons > 8),
+    }
+
+    // Draining hands back the hashes, and only hashes keys again without the feature.
+    let rehashes = Cell::new(0);
+    let drained = node.drain_where(|key| { rehashes.set(rehashes.get() + 1); hash(key.0) }, |hash_val| hash_val % 2 == 0);
+    assert_eq!(drained.len(), 4);
+    assert!(drained.iter().all(|(hash_val, key, _, _)| *hash_val == hash(key.0)));
+    assert_eq!(rehashes.get(), if cfg!(feature = "full-hash"){ 0 } else { 8 });
+    assert_eq!(node.len(), 4);
+}
+
+#[test]
+pub fn node10_entry_api(){
+    use std::sync::Arc;
+    use std::time::Duration;
+    use super::node::Node;
+    use super::node_impl::NodeImpl;
+    use super::weigher::Budget;
+
+    let mut node = Node::<u64,u64>::new();
+    assert_eq!(node.get_or_insert_with(1, 1, || 10), Some(&10));
+    assert_eq!(node.get_or_insert_with(1, 1, || 20), Some(&10));
+
+    assert_eq!(node.and_modify(1, &1, |value| *value += 1), Some(&11));
+    assert_eq!(node.and_modify(2, &2, |value| *value += 1), None);
+    assert_eq!(node.get(2, &2), None);
+
+    // `compute` inserts, updates and removes.
+    let counter = |value: Option<&u64>| Some(value.map_or(1, |count| count + 1));
+    assert_eq!(node.compute(2, 2, counter), Some(&1));
+    assert_eq!(node.compute(2, 2, counter), Some(&2));
+    assert_eq!(node.compute(2, 2, |_| None), None);
+    assert_eq!(node.compute(3, 3, |_| None), None);
+    assert_eq!(node.len(), 1);
+
+    assert_eq!(node.remove_if(1, &1, |&value| value > 100), None);
+    assert_eq!(node.remove_if(1, &1, |&value| value == 11), Some((1, 11)));
+    assert!(node.is_empty());
+
+    // Changes in place keep the TTL.
+    node.insert_with_ttl(4, 4, 4, Duration::from_secs(60));
+    node.and_modify(4, &4, |value| *value = 40);
+    node.compute(4, 4, |value| value.map(|value| value + 1));
+    assert_eq!(node.get(4, &4), Some(&41));
+    assert!(node.ttl(4, &4).is_some());
+
+    // A value growing past the budget makes room by evicting others.
+    let weigher = |_: &u64, value: &Vec<u8>| value.len() as u32;
+    let budget = Arc::new(Budget::new(1000));
+    let mut node = Node::<u64,Vec<u8>>::new().with_weigher(weigher, budget.clone());
+    for key in 0..10u64{
+        node.insert(key, key, vec![0; 100]);
+    }
+    assert_eq!(node.and_modify(9, &9, |value| value.resize(300, 0)).map(Vec::len), Some(300));
+    assert_eq!((node.len(), budget.used()), (8, 1000));
+    assert_eq!(node.get(0, &0), None);
+    // One that cannot fit at all is removed, leaving the others in place.
+    assert_eq!(node.and_modify(9, &9, |value| value.resize(2000, 0)), None);
+    assert_eq!((node.len(), budget.used()), (7, 700));
+    assert_eq!(node.get(9, &9), None);
+}
+
+#[test]
+pub fn node11_borrowed_keys(){
+    use std::sync::Arc;
+    use xxhash_rust::xxh3::xxh3_64;
+    use super::concurrent_node::ConcurrentNode;
+    use super::node::Node;
+    use super::node_impl::NodeImpl;
+
+    let hash = |key: &str| xxh3_64(key.as_bytes());
+    let mut node = Node::<String,u64>::new().with_tag_line();
+    for key in 0..1000u64{
+        node.insert(hash(&key.to_string()), key.to_string(), key);
+    }
+    // Lookups by `&str` find the `String` keys.
+    assert_eq!(node.get(hash("42"), "42"), Some(&42));
+    assert_eq!(node.get(hash("1000"), "1000"), None);
+    assert!(node.handle(hash("7"), "7").is_some());
+    assert_eq!(node.and_modify(hash("7"), "7", |value| *value += 1), Some(&8));
+    assert_eq!(node.remove_if(hash("8"), "8", |_| true), Some((String::from("8"), 8)));
+    assert_eq!(node.delete(hash("9"), "9"), Some((String::from("9"), 9)));
+    assert_eq!(node.len(), 998);
+
+    let node = ConcurrentNode::<Vec<u8>,u64>::new();
+    node.insert(xxh3_64(b"owl"), b"owl".to_vec(), 1);
+    assert_eq!(node.get(xxh3_64(b"owl"), &b"owl"[..]).map(|value| *value), Some(1));
+    assert_eq!(node.remove(xxh3_64(b"owl"), &b"owl"[..]), Some(Arc::new(1)));
+    assert!(node.get(xxh3_64(b"owl"), &b"owl"[..]).is_none());
+}
+
+#[test]
+pub fn node12_unordered_keys_and_values_without_default(){
+    use std::sync::Arc;
+    use super::node::Node;
+    use super::node_impl::NodeImpl;
+
+    /// A key that is neither ordered nor has a default.
+    #[derive(Debug, Hash, PartialEq, Eq)]
+    struct Key(&'static str, u64);
+
+    trait Handler: Send + Sync{
+        fn handle(&self) -> u64;
+    }
+
+    struct Double(u64);
+
+    impl Handler for Double{
+        fn handle(&self) -> u64{
+            self.0 * 2
+        }
+    }
+
+    let mut node = Node::<Key,Arc<dyn Handler>>::new();
+    for id in 0..100u64{
+        node.insert(id, Key("handler", id), Arc::new(Double(id)));
+    }
+    assert_eq!(node.get(7, &Key("handler", 7)).map(|handler| handler.handle()), Some(14));
+    let (key, handler) = node.delete(8, &Key("handler", 8)).unwrap();
+    assert_eq!((key, handler.handle()), (Key("handler", 8), 16));
+    node.insert(8, Key("handler", 8), Arc::new(Double(80)));
+    assert_eq!(node.get(8, &Key("handler", 8)).map(|handler| handler.handle()), Some(160));
+    assert_eq!(node.len(), 100);
+}
+
+#[test]
+pub fn node13_drops_every_entry_once(){
+    use std::sync::Arc;
+    use std::time::Duration;
+    use super::array::ARR_SIZE;
+    use super::node::Node;
+    use super::node_impl::NodeImpl;
+
+    // Keys and values share `root`, which gets back to a single owner only if
+    // every one of them is dropped exactly once.
+    let root = Arc::new(());
+    let key = |id: u64| (id, Arc::clone(&root));
+    {
+        let mut node = Node::<(u64, Arc<()>),Arc<()>>::new();
+        // Fill past capacity to evict, then overwrite, remove and expire entries.
+        for id in 0..ARR_SIZE as u64 + 1000{
+            node.insert(id, key(id), Arc::clone(&root));
+        }
+        for id in 2000..2100u64{
+            assert!(node.insert(id, key(id), Arc::clone(&root)).is_some());
+        }
+        for id in 3000..3100u64{
+            assert!(node.delete(id, &key(id)).is_some());
+        }
+        for id in 4000..4100u64{
+            node.insert_with_ttl(id, key(id), Arc::clone(&root), Duration::from_millis(1));
+        }
+        std::thread::sleep(Duration::from_millis(5));
+        for id in 4000..4100u64{
+            assert_eq!(node.get(id, &key(id)), None);
+        }
+        assert_eq!(node.len(), ARR_SIZE as usize - 200);
+        assert_eq!(Arc::strong_count(&root), 1 + 2 * node.len());
+    }
+    assert_eq!(Arc::strong_count(&root), 1);
+
+    let owl = crate::core_owl::owl::owl::Owl::<u64,Arc<()>>::new(2);
+    for id in 0..1000u64{
+        owl.insert(id, Arc::clone(&root));
+    }
+    owl.clear();
+    assert_eq!(Arc::strong_count(&root), 1);
+}
+
+#[test]
+pub fn node14_overwrite_past_budget(){
+    use std::sync::Arc;
+    use crate::core_owl::node::node::Node;
+    use crate::core_owl::node::node_impl::NodeImpl;
+    use crate::core_owl::node::weigher::Budget;
+
+    let weigher = |_: &u64, value: &Vec<u8>| value.len() as u32;
+    let budget = Arc::new(Budget::new(1000));
+    let mut node = Node::<u64,Vec<u8>>::new().with_weigher(weigher, budget.clone());
+    for key in 0..4u64{
+        node.insert(key, key, vec![key as u8; 100]);
+    }
+
+    // A new value heavier than the whole budget is handed back, leaving the old
+    // one in place, under the same handle.
+    let handle = node.handle(1, &1).unwrap();
+    assert_eq!(node.try_insert(1, 1, vec![0; 2000], None), Err((1, vec![0; 2000])));
+    assert_eq!(node.get(1, &1), Some(&vec![1; 100]));
+    assert!(node.is_live(handle));
+    assert_eq!((node.len(), budget.used()), (4, 400));
+
+    // One that fits still replaces it, in place.
+    assert_eq!(node.insert(1, 1, vec![0; 200]), Some(vec![1; 100]));
+    assert!(node.is_live(handle));
+    assert_eq!((node.len(), budget.used()), (4, 500));
+}
+
+#[test]
+pub fn node15_tags_of_one_owl_node(){
+    use std::hash::BuildHasher;
+    use crate::core_owl::hash::XXBuildHasher;
+    use crate::core_owl::owl_ring::Ring;
+
+    // Keys hashed and routed to one node as an `Owl` of 64 nodes does, which all
+    // share a few ring positions.
+    let (hasher, ring) = (XXBuildHasher::with_seed(10_273), Ring::new(64));
+    let hashes: Vec<u64> = (0..u64::MAX)
+        .map(|key| hasher.hash_one(key))
+        .filter(|&hash| ring.route(hash) == 0)
+        .take(60_000)
+        .collect();
+    let mut tags = super::tag_line::TagLine::new();
+    for (idx, &hash) in hashes.iter().enumerate(){
+        tags.insert(hash, idx as u16);
+    }
+    // Their tags must still tell them apart, so a lookup seldom compares keys twice.
+    let mut compared = 0;
+    for (idx, &hash) in hashes.iter().enumerate(){
+        let found = tags.find(hash, |slot| {
+            compared += 1;
+            slot == idx as u16
+        });
+        assert_eq!(found, Some(idx as u16));
+    }
+    assert!(compared < hashes.len() * 11 / 10, "{compared} comparisons for {} lookups", hashes.len());
+}
+
+#[test]
+pub fn node16_handles_of_evicted_entities(){
+    use xxhash_rust::xxh3::xxh3_64;
+    use super::node::Node;
+    use super::node_impl::NodeImpl;
+
+    let hash = |key: u64| xxh3_64(&key.to_le_bytes());
+    let mut node = Node::<u64,u64>::new();
+    for key in 0..65521u64{
+        node.insert(hash(key), key, key);
+    }
+    let stale = node.handle(hash(0), &0).unwrap();
+
+    // The node is full, so the new key evicts the least recently used one and
+    // takes its slot in the slot's next generation.
+    node.insert(hash(100_000), 100_000, 1);
+    assert_eq!(node.get(hash(0), &0), None);
+    assert!(!node.is_live(stale));
+    assert_eq!(node.get_by_handle(stale), None);
+    let fresh = node.handle(hash(100_000), &100_000).unwrap();
+    assert_eq!(fresh.idx, stale.idx);
+    assert_ne!(fresh.gen, stale.gen);
+    assert_eq!(node.get_by_handle(fresh), Some(&1));
+}
+
+#[test]
+pub fn node17_budget_reservations(){
+    use std::sync::Arc;
+    use std::thread;
+    use super::weigher::Budget;
+
+    // Threads reserving concurrently never take the budget past its limit.
+    let budget = Arc::new(Budget::new(500));
+    let workers: Vec<_> = (0..8).map(|_| {
+        let budget = Arc::clone(&budget);
+        thread::spawn(move || (0..1000).filter(|_| budget.try_reserve(1)).count())
+    }).collect();
+    let reserved: usize = workers.into_iter().map(|worker| worker.join().unwrap()).sum();
+    assert_eq!((reserved, budget.used()), (500, 500));
+    assert!(!budget.try_reserve(1));
+    budget.refund(500);
+    assert!(budget.try_reserve(500));
+}
+
+#[test]
+pub fn node18_tag_line_without_chains(){
+    use std::panic::catch_unwind;
+    use super::node::Node;
+    use super::node_impl::NodeImpl;
+
+    // Keys sharing one hash are told apart by the tag line alone.
+    let mut node = Node::<u32,u32>::new().with_tag_line();
+    for key in 0..5{
+        node.insert(42, key, key);
+    }
+    assert_eq!(node.delete(42, &2), Some((2, 2)));
+    assert_eq!(node.delete(42, &4), Some((4, 4)));
+    assert_eq!(node.delete(42, &0), Some((0, 0)));
+    assert_eq!(node.get(42, &1), Some(&1));
+    assert_eq!(node.get(42, &3), Some(&3));
+    assert_eq!(node.get(42, &2), None);
+    node.insert(42, 5, 5);
+    assert_eq!(node.get(42, &5), Some(&5));
+    assert_eq!(node.len(), 3);
+
+    // Lock-free readers walk chains, which a node with a tag line does not keep.
+    assert!(catch_unwind(|| Node::<u32,u32>::new().with_tag_line().share_reads()).is_err());
+}
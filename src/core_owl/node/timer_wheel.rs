@@ -0,0 +1,172 @@
+use super::array::ARR_SIZE;
+use super::NULL_IDX;
+
+/// Milliseconds per tick of the lowest level.
+const TICK_MS: u64 = 1000;
+
+/// Buckets and tick span of the seconds, minutes and hours levels.
+const LEVELS: [(u64, u64); 3] = [(60, 1), (60, 60), (24, 3600)];
+
+/// Index of the first bucket of every level in `TimerWheel::heads`.
+const LEVEL_OFFSET: [u16; 3] = [0, 60, 120];
+
+/// Bucket holding the slots due in more than a day, rescheduled once per day.
+const OVERFLOW: u16 = 144;
+
+/// Total number of buckets, overflow included.
+const BUCKETS: usize = OVERFLOW as usize + 1;
+
+/// A hierarchical timing wheel of slot indices.
+///
+/// The wheel has a seconds level of 60 one-second buckets, a minutes level of 60
+/// one-minute buckets, an hours level of 24 one-hour buckets and an overflow
+/// bucket. Slots due within a minute sit in the seconds level, the others sit in
+/// a coarser bucket until it comes up and cascades them one level down. Every
+/// slot is scheduled O(1) and cascaded at most three times, and advancing the
+/// wheel only visits the buckets of the elapsed ticks.
+///
+/// Buckets are doubly linked lists threaded through per-slot `u16` links, so the
+/// wheel never allocates after creation and a slot can be cancelled in O(1).
+pub struct TimerWheel {
+    /// First slot of every bucket, `NULL_IDX` when empty.
+    heads: [u16; BUCKETS],
+
+    /// Next slot in the same bucket.
+    next: Box<[u16]>,
+
+    /// Previous slot in the same bucket.
+    prev: Box<[u16]>,
+
+    /// Bucket each slot is scheduled in, `NULL_IDX` when it is not scheduled.
+    bucket: Box<[u16]>,
+
+    /// Last tick that was processed.
+    current: u64,
+}
+
+impl TimerWheel {
+    /// Creates a wheel with nothing scheduled, starting at tick `0`.
+    pub fn new() -> Self {
+        let line = || vec![NULL_IDX; ARR_SIZE as usize].into_boxed_slice();
+        TimerWheel {
+            heads: [NULL_IDX; BUCKETS],
+            next: line(),
+            prev: line(),
+            bucket: line(),
+            current: 0,
+        }
+    }
+
+    /// Schedules the slot at `idx` to fire at `deadline` milliseconds.
+    ///
+    /// The slot must not already be scheduled. A deadline in the past fires on
+    /// the next tick.
+    pub fn schedule(&mut self, idx: u16, deadline: u64) {
+        self.place(idx, deadline, self.current + 1);
+    }
+
+    /// Links the slot at `idx` into the bucket of `deadline`, firing no earlier
+    /// than tick `earliest`.
+    fn place(&mut self, idx: u16, deadline: u64, earliest: u64) {
+        let tick = deadline.div_ceil(TICK_MS).max(earliest);
+        let delta = tick - self.current;
+        let bucket = LEVELS
+            .iter()
+            .zip(LEVEL_OFFSET)
+            .find(|((buckets, span), _)| delta < buckets * span)
+            .map(|((buckets, span), offset)| offset + (tick / span % buckets) as u16)
+            .unwrap_or(OVERFLOW);
+
+        let head = self.heads[bucket as usize];
+        self.next[idx as usize] = head;
+        self.prev[idx as usize] = NULL_IDX;
+        if head != NULL_IDX {
+            self.prev[head as usize] = idx;
+        }
+        self.heads[bucket as usize] = idx;
+        self.bucket[idx as usize] = bucket;
+    }
+
+    /// Removes the slot at `idx` from the wheel, if it is scheduled.
+    pub fn cancel(&mut self, idx: u16) {
+        let bucket = self.bucket[idx as usize];
+        if bucket == NULL_IDX {
+            return;
+        }
+        let (prev, next) = (self.prev[idx as usize], self.next[idx as usize]);
+        match prev == NULL_IDX {
+            true => self.heads[bucket as usize] = next,
+            false => self.next[prev as usize] = next,
+        }
+        if next != NULL_IDX {
+            self.prev[next as usize] = prev;
+        }
+        self.bucket[idx as usize] = NULL_IDX;
+    }
+
+    /// Detaches a whole bucket and returns its first slot.
+    /// The detached slots stay chained through `next`.
+    fn take_bucket(&mut self, bucket: u16) -> u16 {
+        let head = self.heads[bucket as usize];
+        self.heads[bucket as usize] = NULL_IDX;
+        let mut idx = head;
+        while idx != NULL_IDX {
+            self.bucket[idx as usize] = NULL_IDX;
+            idx = self.next[idx as usize];
+        }
+        head
+    }
+
+    /// Reschedules every slot of `bucket` relative to the current tick.
+    /// Slots due on the current tick land in the seconds bucket about to fire.
+    fn cascade(&mut self, bucket: u16, deadlines: &[u64]) {
+        let mut idx = self.take_bucket(bucket);
+        while idx != NULL_IDX {
+            let next = self.next[idx as usize];
+            self.place(idx, deadlines[idx as usize], self.current);
+            idx = next;
+        }
+    }
+
+    /// Processes every tick up to `now` milliseconds.
+    ///
+    /// # Arguments
+    /// * `now` - Current time in milliseconds, on the same clock as the deadlines.
+    /// * `deadlines` - Deadline of every slot, used to cascade coarse buckets.
+    /// * `expired` - Receives every slot whose deadline has passed. Those slots
+    ///   are no longer scheduled.
+    pub fn advance(&mut self, now: u64, deadlines: &[u64], expired: &mut Vec<u16>) {
+        let now_tick = now / TICK_MS;
+        while self.current < now_tick {
+            self.current += 1;
+            let tick = self.current;
+
+            // Cascade coarse buckets first, so their slots can fire on this very tick.
+            if tick.is_multiple_of(86_400) {
+                self.cascade(OVERFLOW, deadlines);
+            }
+            for level in (1..LEVELS.len()).rev() {
+                let (buckets, span) = LEVELS[level];
+                if tick.is_multiple_of(span) {
+                    self.cascade(LEVEL_OFFSET[level] + (tick / span % buckets) as u16, deadlines);
+                }
+            }
+
+            let mut idx = self.take_bucket((tick % LEVELS[0].0) as u16);
+            while idx != NULL_IDX {
+                let next = self.next[idx as usize];
+                match deadlines[idx as usize] <= now {
+                    true => expired.push(idx),
+                    false => self.schedule(idx, deadlines[idx as usize]),
+                }
+                idx = next;
+            }
+        }
+    }
+}
+
+impl Default for TimerWheel {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,109 @@
+//! Weighted capacity for a `Node`.
+//!
+//! By default a node is bounded by its number of slots. A `Weigher` assigns every
+//! entry a weight, usually its size in bytes, and a `Budget` caps the sum of the
+//! weights. A budget can belong to a single node or be shared by many of them.
+
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// Computes the weight of an entry, typically the number of bytes it holds.
+pub trait Weigher<K, V> {
+    /// Returns the weight of the entry `key` -> `value`.
+    fn weight(&self, key: &K, value: &V) -> u32;
+}
+
+impl<K, V, F> Weigher<K, V> for F
+where
+    F: Fn(&K, &V) -> u32,
+{
+    #[inline(always)]
+    fn weight(&self, key: &K, value: &V) -> u32 {
+        self(key, value)
+    }
+}
+
+/// Upper bound on the total weight of the entries of one or more nodes.
+///
+/// Wrap it in an `Arc` and hand a clone to every node that should share it. A
+/// node over budget evicts its own entries only. So that its peers cannot starve
+/// it, a node holding less than its share, the limit split evenly between the
+/// nodes, may still take weight once the budget is spent. The total then goes
+/// over the limit until the nodes above their share evict on their next inserts.
+#[derive(Debug)]
+pub struct Budget {
+    /// Maximum total weight.
+    limit: u64,
+
+    /// Total weight currently charged.
+    used: AtomicU64,
+
+    /// Number of nodes charging the budget.
+    nodes: AtomicU64,
+}
+
+impl Budget {
+    /// Creates a budget of `limit`, nothing charged yet.
+    pub const fn new(limit: u64) -> Self {
+        Budget {
+            limit,
+            used: AtomicU64::new(0),
+            nodes: AtomicU64::new(0),
+        }
+    }
+
+    /// Returns the maximum total weight.
+    #[inline(always)]
+    pub fn limit(&self) -> u64 {
+        self.limit
+    }
+
+    /// Returns the total weight currently charged.
+    #[inline(always)]
+    pub fn used(&self) -> u64 {
+        self.used.load(Ordering::Relaxed)
+    }
+
+    /// Returns the weight every node sharing the budget is entitled to.
+    #[inline(always)]
+    pub fn share(&self) -> u64 {
+        self.limit / self.nodes.load(Ordering::Relaxed).max(1)
+    }
+
+    /// Charges `weight` if it fits under the limit, in a single step, so that
+    /// nodes sharing the budget cannot overshoot it together.
+    ///
+    /// # Returns
+    /// `false`, charging nothing, if `weight` does not fit.
+    #[inline(always)]
+    pub fn try_reserve(&self, weight: u64) -> bool {
+        self.used
+            .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |used| {
+                used.checked_add(weight).filter(|&used| used <= self.limit)
+            })
+            .is_ok()
+    }
+
+    /// Adds `weight` to the charged total.
+    #[inline(always)]
+    pub fn charge(&self, weight: u64) {
+        self.used.fetch_add(weight, Ordering::Relaxed);
+    }
+
+    /// Removes `weight` from the charged total.
+    #[inline(always)]
+    pub fn refund(&self, weight: u64) {
+        self.used.fetch_sub(weight, Ordering::Relaxed);
+    }
+
+    /// Counts one more node charging the budget.
+    #[inline(always)]
+    pub fn join(&self) {
+        self.nodes.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Counts one node less charging the budget.
+    #[inline(always)]
+    pub fn leave(&self) {
+        self.nodes.fetch_sub(1, Ordering::Relaxed);
+    }
+}
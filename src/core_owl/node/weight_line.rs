@@ -0,0 +1,85 @@
+use std::sync::Arc;
+
+use super::array::ARR_SIZE;
+use super::weigher::{Budget, Weigher};
+
+/// `WeightLine` stores the weight of every slot of a `DataLine`, which the node
+/// reserves on its `Budget` beforehand, and refunds it when the slot is freed.
+pub struct WeightLine<K, V> {
+    /// Computes the weight of new entries.
+    weigher: Box<dyn Weigher<K, V> + Send + Sync>,
+
+    /// Budget every weight is charged to, possibly shared with other nodes.
+    budget: Arc<Budget>,
+
+    /// Weight of the entry stored in every slot, `0` for free slots.
+    weights: Box<[u32]>,
+
+    /// Sum of the weights of this line.
+    total: u64,
+}
+
+impl<K, V> WeightLine<K, V> {
+    /// Creates a line where every slot weighs nothing.
+    pub fn new(weigher: Box<dyn Weigher<K, V> + Send + Sync>, budget: Arc<Budget>) -> Self {
+        budget.join();
+        WeightLine {
+            weigher,
+            budget,
+            weights: vec![0; ARR_SIZE as usize].into_boxed_slice(),
+            total: 0,
+        }
+    }
+
+    /// Returns the weight of the entry `key` -> `value`.
+    #[inline(always)]
+    pub fn weigh(&self, key: &K, value: &V) -> u32 {
+        self.weigher.weight(key, value)
+    }
+
+    /// Returns the budget of the line.
+    #[inline(always)]
+    pub fn budget(&self) -> &Budget {
+        &self.budget
+    }
+
+    /// Returns the sum of the weights of this line.
+    #[inline(always)]
+    pub fn total(&self) -> u64 {
+        self.total
+    }
+
+    /// Returns the weight of the entry in slot `idx`.
+    #[inline(always)]
+    pub fn get(&self, idx: u16) -> u32 {
+        self.weights[idx as usize]
+    }
+
+    /// Records that slot `idx` now holds an entry of `weight`, which is already
+    /// charged to the budget beyond what the slot held before. A lighter entry
+    /// refunds the difference.
+    #[inline(always)]
+    pub fn set(&mut self, idx: u16, weight: u32) {
+        let old = std::mem::replace(&mut self.weights[idx as usize], weight);
+        self.total = self.total - old as u64 + weight as u64;
+        if weight < old {
+            self.budget.refund((old - weight) as u64);
+        }
+    }
+
+    /// Records that slot `idx` is free, refunding its weight.
+    #[inline(always)]
+    pub fn clear(&mut self, idx: u16) {
+        let weight = std::mem::take(&mut self.weights[idx as usize]);
+        self.total -= weight as u64;
+        self.budget.refund(weight as u64);
+    }
+}
+
+impl<K, V> Drop for WeightLine<K, V> {
+    /// Hands the weight of the remaining entries back to a shared budget.
+    fn drop(&mut self) {
+        self.budget.refund(self.total);
+        self.budget.leave();
+    }
+}
@@ -0,0 +1,10 @@
+//! The `Owl` cache, the entry point of the crate.
+//!
+//! An `Owl` hashes every key once, routes it through the consistency `Ring` to
+//! one of its `Node`s, and hands the same hash to that node, which never hashes
+//! again. Each node has its own lock, so an `Owl` is shared between threads
+//! through `&self`.
+
+#[allow(clippy::module_inception)]
+pub mod owl;
+mod test;
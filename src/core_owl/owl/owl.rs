@@ -0,0 +1,796 @@
+use std::borrow::Borrow;
+use std::cell::Cell;
+use std::collections::BTreeSet;
+use std::hash::{BuildHasher, Hash};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering::{Acquire, Relaxed, Release}};
+use std::sync::{Arc, Mutex, MutexGuard, RwLock, RwLockReadGuard, RwLockWriteGuard};
+use std::time::Duration;
+
+use super::super::hash::XXBuildHasher;
+use super::super::node::{node::Node, node_impl::NodeImpl, policy::{EvictionPolicy, Lru}, ARR_SIZE};
+use super::super::owl_ring::Ring;
+use super::super::router::Router;
+
+/// Number of inserts between two automatic rebalances of the router.
+const REBALANCE_EVERY: usize = ARR_SIZE as usize;
+
+/// Ratio between the hottest node and the mean concentration tolerated by the
+/// automatic rebalance.
+const REBALANCE_SKEW: f64 = 1.5;
+
+/// Evictions between two rebalances past which a node is split.
+const SPLIT_EVICTIONS: u64 = ARR_SIZE as u64 / 8;
+
+/// Combined number of entries under which two neighbouring nodes are merged.
+const MERGE_LEN: usize = ARR_SIZE as usize / 4;
+
+/// One access out of `RECORD_EVERY`, on average, is reported to the router.
+const RECORD_EVERY: u64 = 16;
+
+thread_local! {
+    /// Xorshift state sampling the accesses of the current thread.
+    static SAMPLER: Cell<u64> = const { Cell::new(0x9E37_79B9_7F4A_7C15) };
+}
+
+/// A cache made of `Node`s, each owning a share of the hashes given by a `Router`,
+/// by default the arcs of the consistency `Ring`.
+///
+/// Capacity is expressed as a number of nodes, every node holding up to
+/// `ARR_SIZE` entries and evicting the least recently used one once full.
+/// `with_nodes` builds the nodes through a factory instead, to pick their
+/// eviction policy, admission filter, weight budget or lookup index.
+///
+/// The cache is `Send + Sync` and every method takes `&self`, so it is meant to be
+/// shared as an `Arc<Owl<K, V>>`. Every node sits behind its own `Mutex`, so
+/// threads working on keys of different nodes never wait for each other. The
+/// nodes and the router sit together behind a `RwLock`, only taken for writing
+/// while a node is added or removed.
+///
+/// A random sample of the reads and inserts is reported to the router. Every
+/// `REBALANCE_EVERY` inserts, a ring moves arc boundaries away from nodes
+/// carrying far more than their share, and the entries of the moved ranges
+/// follow them. This is done by an inserting thread only if no other thread is
+/// rebalancing or resizing the cache, otherwise a later insert tries again; a
+/// maintenance thread can also call `rebalance` and `resize` itself.
+///
+/// Within the limits set by `with_node_limits`, the cache also resizes itself at
+/// that point: a node that evicted more than `SPLIT_EVICTIONS` entities is split
+/// in two, and neighbouring nodes holding fewer than `MERGE_LEN` entries between
+/// them are merged. Only the entries the router sends elsewhere move.
+///
+/// Entries move while every other operation goes on. The new partitioning is
+/// published at once, then the entries are moved one pair of nodes at a time,
+/// under the locks of those two nodes only. Until they are all moved, an
+/// operation on a key first moves its entry out of the node it lived in, if it
+/// is still there.
+///
+/// # Generics
+/// - `K`: Key type, must support hashing and equality.
+/// - `V`: Value type.
+/// - `S`: Builds the hasher applied to every key, `XXBuildHasher` by default.
+/// - `R`: Routes hashes to nodes, `Ring` by default.
+/// - `P`: Eviction policy of the nodes, `Lru` by default.
+pub struct Owl<K, V, S = XXBuildHasher, R = Ring, P = Lru>
+where
+    K: Hash + Eq,
+    P: EvictionPolicy<K, V>,
+{
+    /// The nodes and the router partitioning the hashes between them.
+    shards: RwLock<Shards<K, V, R, P>>,
+
+    /// Builds every node, on creation, on `clear` and on splits.
+    factory: Factory<K, V, P>,
+
+    /// Hashes the keys.
+    hasher: S,
+
+    /// Inserts since the last rebalance.
+    inserts: AtomicUsize,
+
+    /// Held by the thread rebalancing or resizing the cache.
+    maintenance: Mutex<()>,
+
+    /// Fewest nodes merges can leave.
+    min_nodes: usize,
+
+    /// Most nodes splits can create.
+    max_nodes: usize,
+}
+
+/// The nodes of an `Owl` together with the router owning their ids, so that both
+/// are always changed at once.
+struct Shards<K, V, R, P>
+where
+    K: Hash + Eq,
+    P: EvictionPolicy<K, V>,
+{
+    /// The nodes, indexed by the router.
+    nodes: Vec<SharedNode<K, V, P>>,
+
+    /// Routes every hash to the node owning it.
+    router: R,
+
+    /// Set while entries move to the nodes the router now sends them to.
+    moving: AtomicBool,
+
+    /// Where entries lived before the partitioning changed, while they move.
+    previous: RwLock<Option<Previous<K, V, R, P>>>,
+}
+
+/// The partitioning of an `Owl` before its last change.
+struct Previous<K, V, R, P>
+where
+    K: Hash + Eq,
+    P: EvictionPolicy<K, V>,
+{
+    /// The nodes, including the one a merge removed.
+    nodes: Vec<SharedNode<K, V, P>>,
+
+    /// Routes every hash to the node it lived in.
+    router: R,
+}
+
+/// A node of an `Owl`, also held by the partitioning entries move away from.
+type SharedNode<K, V, P> = Arc<Mutex<Node<K, V, P>>>;
+
+/// A locked node of an `Owl`.
+type NodeGuard<'a, K, V, P> = MutexGuard<'a, Node<K, V, P>>;
+
+/// Builds the nodes of an `Owl`.
+enum Factory<K, V, P>
+where
+    K: Hash + Eq,
+    P: EvictionPolicy<K, V>,
+{
+    /// A plain function, such as `Node::new`, which keys and values of any
+    /// lifetime can use.
+    Fn(fn() -> Node<K, V, P>),
+
+    /// A closure given to `Owl::with_nodes`.
+    Closure(Box<dyn Fn() -> Node<K, V, P> + Send + Sync>),
+}
+
+impl<K, V, P> Factory<K, V, P>
+where
+    K: Hash + Eq,
+    P: EvictionPolicy<K, V>,
+{
+    /// Returns a new, empty node.
+    fn build(&self) -> Node<K, V, P> {
+        match self {
+            Factory::Fn(factory) => factory(),
+            Factory::Closure(factory) => factory(),
+        }
+    }
+}
+
+impl<K, V> Owl<K, V, XXBuildHasher>
+where
+    K: Hash + Eq,
+{
+    /// Creates an empty cache of `nodes` nodes, hashing keys with xxh3 and a
+    /// seed of its own.
+    ///
+    /// # Arguments
+    /// * `nodes` - Number of nodes, between `1` and `RING_SIZE`.
+    pub fn new(nodes: usize) -> Self {
+        Self::with_hasher(nodes, XXBuildHasher::new())
+    }
+}
+
+impl<K, V, S> Owl<K, V, S>
+where
+    K: Hash + Eq,
+    S: BuildHasher,
+{
+    /// Creates an empty cache of `nodes` nodes, hashing keys with `hasher`.
+    ///
+    /// # Arguments
+    /// * `nodes` - Number of nodes, between `1` and `RING_SIZE`.
+    /// * `hasher` - Builds the hasher applied to every key.
+    pub fn with_hasher(nodes: usize, hasher: S) -> Self {
+        Self::with_router(Ring::new(nodes), hasher)
+    }
+}
+
+impl<K, V, S, P> Owl<K, V, S, Ring, P>
+where
+    K: Hash + Eq,
+    S: BuildHasher,
+    P: EvictionPolicy<K, V>,
+{
+    /// Splits every node into `vnodes` arcs of the ring instead of the default.
+    ///
+    /// # Panics
+    /// If the cache already holds entries, or if there would be more arcs than
+    /// ring positions.
+    pub fn with_vnodes(mut self, vnodes: usize) -> Self {
+        assert!(self.is_empty(), "virtual nodes can only be set on an empty cache");
+        let shards = self.shards.get_mut().unwrap();
+        shards.router = Ring::with_vnodes(shards.nodes.len(), vnodes);
+        self
+    }
+}
+
+impl<K, V, S, R> Owl<K, V, S, R>
+where
+    K: Hash + Eq,
+    S: BuildHasher,
+    R: Router,
+{
+    /// Creates an empty cache of one node per node of `router`.
+    ///
+    /// # Arguments
+    /// * `router` - Routes hashes to nodes.
+    /// * `hasher` - Builds the hasher applied to every key.
+    pub fn with_router(router: R, hasher: S) -> Self {
+        let nodes = router.nodes();
+        Owl {
+            shards: RwLock::new(Shards::new((0..nodes).map(|_| Node::new()).collect(), router)),
+            factory: Factory::Fn(Node::new),
+            hasher,
+            inserts: AtomicUsize::new(0),
+            maintenance: Mutex::new(()),
+            min_nodes: nodes,
+            max_nodes: nodes,
+        }
+    }
+}
+
+impl<K, V, S, R, P> Owl<K, V, S, R, P>
+where
+    K: Hash + Eq,
+    S: BuildHasher,
+    R: Router,
+    P: EvictionPolicy<K, V>,
+{
+    /// Builds every node of the cache with `factory`, now and whenever a node
+    /// is added or cleared, instead of `Node::new`. The factory sets the
+    /// eviction policy and whatever else nodes support, such as an admission
+    /// filter, or a weigher charging a `Budget` all nodes share.
+    ///
+    /// # Arguments
+    /// * `factory` - Returns a new, empty node every time it is called.
+    ///
+    /// # Panics
+    /// If the cache already holds entries.
+    pub fn with_nodes<Q, F>(self, factory: F) -> Owl<K, V, S, R, Q>
+    where
+        Q: EvictionPolicy<K, V>,
+        F: Fn() -> Node<K, V, Q> + Send + Sync + 'static,
+    {
+        assert!(self.is_empty(), "nodes can only be configured on an empty cache");
+        let shards = self.shards.into_inner().unwrap();
+        Owl {
+            shards: RwLock::new(Shards::new(shards.nodes.iter().map(|_| factory()).collect(), shards.router)),
+            factory: Factory::Closure(Box::new(factory)),
+            hasher: self.hasher,
+            inserts: self.inserts,
+            maintenance: self.maintenance,
+            min_nodes: self.min_nodes,
+            max_nodes: self.max_nodes,
+        }
+    }
+
+    /// Lets the cache grow up to `max` nodes by splitting nodes that thrash, and
+    /// shrink down to `min` nodes by merging nearly empty ones. By default the
+    /// node count stays fixed.
+    ///
+    /// # Panics
+    /// If `min` is `0` or greater than `max`.
+    pub fn with_node_limits(mut self, min: usize, max: usize) -> Self {
+        assert!(min >= 1 && min <= max, "invalid node limits {min}..={max}");
+        self.min_nodes = min;
+        self.max_nodes = max;
+        self
+    }
+
+    /// Returns the hash of `key`, the same for every borrowed form of a key.
+    #[inline(always)]
+    fn hash<Q: Hash + ?Sized>(&self, key: &Q) -> u64 {
+        self.hasher.hash_one(key)
+    }
+
+    /// Locks the shards for reading, leaving the partitioning untouched.
+    #[inline(always)]
+    fn shards(&self) -> RwLockReadGuard<'_, Shards<K, V, R, P>> {
+        self.shards.read().unwrap()
+    }
+
+    /// Locks the shards for writing, waiting for every other operation to end.
+    #[inline(always)]
+    fn shards_mut(&self) -> RwLockWriteGuard<'_, Shards<K, V, R, P>> {
+        self.shards.write().unwrap()
+    }
+
+    /// Runs `f` on the node owning `key`, holding the lock of that node only.
+    ///
+    /// # Arguments
+    /// * `hash_val` - The hash of `key`.
+    /// * `key` - The key `f` works on.
+    /// * `f` - Receives the locked node and the router.
+    #[inline(always)]
+    fn with_node<Q, T, F>(&self, hash_val: u64, key: &Q, f: F) -> T
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+        F: FnOnce(&mut Node<K, V, P>, &R) -> T,
+    {
+        let shards = self.shards();
+        let mut node = shards.lock_owner(hash_val, key);
+        f(&mut node, &shards.router)
+    }
+
+    /// Runs `f` on the node owning `key` like `with_node`, handing it the key.
+    #[inline(always)]
+    fn with_key_node<T, F>(&self, hash_val: u64, key: K, f: F) -> T
+    where
+        F: FnOnce(&mut Node<K, V, P>, &R, K) -> T,
+    {
+        let shards = self.shards();
+        let mut node = shards.lock_owner(hash_val, &key);
+        f(&mut node, &shards.router, key)
+    }
+
+    /// Retrieves a clone of the value of `key`, counting as an access for eviction.
+    ///
+    /// `key` may be any borrowed form of the key type, like a `&str` for `String`
+    /// keys, as long as it hashes and compares like the key.
+    pub fn get<Q>(&self, key: &Q) -> Option<V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+        V: Clone,
+    {
+        self.get_with(key, V::clone)
+    }
+
+    /// Calls `f` on the value of `key`, counting as an access for eviction.
+    ///
+    /// The node of `key` stays locked while `f` runs, so `f` should be short and
+    /// must not use the cache.
+    ///
+    /// # Returns
+    /// What `f` returned, if the key was present.
+    pub fn get_with<Q, T, F>(&self, key: &Q, f: F) -> Option<T>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+        F: FnOnce(&V) -> T,
+    {
+        let hash_val = self.hash(key);
+        self.with_node(hash_val, key, |node, router| {
+            record(router, hash_val);
+            node.get_with(hash_val, key, f)
+        })
+    }
+
+    /// Inserts a key-value pair, evicting from the key's node if it is full.
+    ///
+    /// # Returns
+    /// The previous value if the key was already present. `None` also when the
+    /// node turned the entry away, use `try_insert` to tell the two apart.
+    pub fn insert(&self, key: K, value: V) -> Option<V> {
+        self.insert_entry(key, value, None).unwrap_or(None)
+    }
+
+    /// Inserts a key-value pair like `insert`, telling apart an entry that was
+    /// turned away from one that was stored.
+    ///
+    /// # Returns
+    /// The previous value if the key was already present, or the key and value
+    /// back if the node's budget or admission filter rejected them.
+    pub fn try_insert(&self, key: K, value: V) -> Result<Option<V>, (K, V)> {
+        self.insert_entry(key, value, None)
+    }
+
+    /// Inserts a key-value pair that expires `ttl` from now.
+    ///
+    /// # Returns
+    /// The previous value if the key was present and not expired.
+    pub fn insert_with_ttl(&self, key: K, value: V, ttl: Duration) -> Option<V> {
+        self.insert_entry(key, value, Some(ttl)).unwrap_or(None)
+    }
+
+    /// Shared implementation of `insert`, `insert_with_ttl` and `try_insert`.
+    fn insert_entry(&self, key: K, value: V, ttl: Option<Duration>) -> Result<Option<V>, (K, V)> {
+        let hash_val = self.hash(&key);
+        let previous = self.with_key_node(hash_val, key, |node, router, key| {
+            record(router, hash_val);
+            node.try_insert(hash_val, key, value, ttl)
+        });
+        self.inserted();
+        previous
+    }
+
+    /// Counts an insert, triggering the periodic rebalance and resize once
+    /// `REBALANCE_EVERY` of them were made, unless another thread is already
+    /// at it. Other inserts never wait for it.
+    fn inserted(&self) {
+        if self.inserts.fetch_add(1, Relaxed) + 1 < REBALANCE_EVERY {
+            return;
+        }
+        if let Ok(_maintenance) = self.maintenance.try_lock() {
+            self.rebalance_shards();
+            self.resize_shards();
+        }
+    }
+
+    /// Returns a clone of the value of `key`, inserting the one made by `default`
+    /// first if the key is absent. Looking up and inserting happen under a
+    /// single lock of the key's node, and the key is hashed once.
+    ///
+    /// The node stays locked while `default` runs, so it must not use the cache.
+    ///
+    /// # Returns
+    /// The value, `None` only if the node refused the new entry.
+    pub fn get_or_insert_with<F>(&self, key: K, default: F) -> Option<V>
+    where
+        F: FnOnce() -> V,
+        V: Clone,
+    {
+        let hash_val = self.hash(&key);
+        let value = self.with_key_node(hash_val, key, |node, router, key| {
+            record(router, hash_val);
+            node.get_or_insert_with(hash_val, key, default).cloned()
+        });
+        self.inserted();
+        value
+    }
+
+    /// Modifies the value of `key` in place, keeping its TTL.
+    ///
+    /// The node of `key` stays locked while `f` runs, so `f` should be short and
+    /// must not use the cache.
+    ///
+    /// # Returns
+    /// `true` if the key was present and modified.
+    pub fn and_modify<Q, F>(&self, key: &Q, f: F) -> bool
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+        F: FnOnce(&mut V),
+    {
+        let hash_val = self.hash(key);
+        self.with_node(hash_val, key, |node, router| {
+            record(router, hash_val);
+            node.and_modify(hash_val, key, f).is_some()
+        })
+    }
+
+    /// Replaces the value of `key` by what `f` computes from the current one,
+    /// atomically with respect to every other operation on the key.
+    ///
+    /// `f` receives `None` if the key is absent. If it returns `None`, the key is
+    /// removed, otherwise the new value is stored, keeping the TTL of a present
+    /// key. The node of `key` stays locked while `f` runs, so `f` should be short
+    /// and must not use the cache.
+    ///
+    /// # Returns
+    /// A clone of the new value, if any.
+    pub fn compute<F>(&self, key: K, f: F) -> Option<V>
+    where
+        F: FnOnce(Option<&V>) -> Option<V>,
+        V: Clone,
+    {
+        let hash_val = self.hash(&key);
+        let value = self.with_key_node(hash_val, key, |node, router, key| {
+            record(router, hash_val);
+            node.compute(hash_val, key, f).cloned()
+        });
+        self.inserted();
+        value
+    }
+
+    /// Removes `key` if `pred` holds for its value, atomically with respect to
+    /// every other operation on the key.
+    ///
+    /// # Returns
+    /// The removed value, `None` if the key is absent or `pred` did not hold.
+    pub fn remove_if<Q, F>(&self, key: &Q, pred: F) -> Option<V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+        F: FnOnce(&V) -> bool,
+    {
+        let hash_val = self.hash(key);
+        self.with_node(hash_val, key, |node, _| node.remove_if(hash_val, key, pred).map(|(_, value)| value))
+    }
+
+    /// Rebalances the router right away and migrates the entries it now routes
+    /// to another node.
+    ///
+    /// # Returns
+    /// The number of entries migrated by this call, not counting the ones
+    /// concurrent operations moved first.
+    pub fn rebalance(&self) -> usize {
+        let _maintenance = self.maintenance.lock().unwrap();
+        self.rebalance_shards()
+    }
+
+    /// Implementation of `rebalance`, for the thread holding `maintenance`.
+    fn rebalance_shards(&self) -> usize {
+        self.inserts.store(0, Relaxed);
+        let shards = self.shards();
+        shards.begin_move(Previous { nodes: shards.nodes.clone(), router: shards.router.clone() });
+        let sources = shards.router.rebalance(REBALANCE_SKEW);
+        let sources: Vec<_> = sources.iter().map(|&from| shards.nodes[from].clone()).collect();
+        let migrated = shards.migrate_from(&self.hasher, &sources);
+        shards.end_move();
+        migrated
+    }
+
+    /// Splits the nodes that evicted more than `SPLIT_EVICTIONS` entities since
+    /// the previous call, then merges nearly empty neighbouring nodes, within
+    /// the node limits.
+    ///
+    /// # Returns
+    /// The number of entries migrated by this call, not counting the ones
+    /// concurrent operations moved first.
+    pub fn resize(&self) -> usize {
+        let _maintenance = self.maintenance.lock().unwrap();
+        self.resize_shards()
+    }
+
+    /// Implementation of `resize`, for the thread holding `maintenance`.
+    ///
+    /// The shards are only locked for writing to change the router and the
+    /// nodes; the entries move afterwards, like for a rebalance.
+    fn resize_shards(&self) -> usize {
+        let mut migrated = 0;
+        let evictions: Vec<u64> =
+            self.shards().nodes.iter().map(|node| node.lock().unwrap().take_evictions()).collect();
+        for (node, evictions) in evictions.into_iter().enumerate() {
+            if evictions < SPLIT_EVICTIONS || self.node_count() >= self.max_nodes {
+                continue;
+            }
+            let sources = {
+                let mut shards = self.shards_mut();
+                let previous = Previous { nodes: shards.nodes.clone(), router: shards.router.clone() };
+                let sources = shards.router.split(node);
+                if sources.is_empty() {
+                    continue;
+                }
+                shards.nodes.push(Arc::new(Mutex::new(self.factory.build())));
+                shards.begin_move(previous);
+                sources.iter().map(|&from| shards.nodes[from].clone()).collect::<Vec<_>>()
+            };
+            let shards = self.shards();
+            migrated += shards.migrate_from(&self.hasher, &sources);
+            shards.end_move();
+        }
+
+        while self.node_count() > self.min_nodes {
+            let sources = {
+                let mut shards = self.shards_mut();
+                let lens: Vec<usize> = shards.nodes.iter().map(|node| node.lock().unwrap().len()).collect();
+                let from = (0..lens.len()).min_by_key(|&node| lens[node]).unwrap_or(0);
+                let into = shards.router.neighbours(from).into_iter().min_by_key(|&node| lens[node]);
+                let into = match into {
+                    Some(into) if lens[from] + lens[into] <= MERGE_LEN => into,
+                    _ => break,
+                };
+                let previous = Previous { nodes: shards.nodes.clone(), router: shards.router.clone() };
+                // The router renumbers the last node as `from`, just like `swap_remove`.
+                let sources = shards.router.merge(from, into);
+                let merged = shards.nodes.swap_remove(from);
+                shards.begin_move(previous);
+                let mut sources: Vec<_> = sources.iter().map(|&from| shards.nodes[from].clone()).collect();
+                sources.push(merged);
+                sources
+            };
+            let shards = self.shards();
+            migrated += shards.migrate_from(&self.hasher, &sources);
+            shards.end_move();
+        }
+        migrated
+    }
+
+    /// Returns the time left before `key` expires, if it was inserted with a TTL.
+    pub fn ttl<Q>(&self, key: &Q) -> Option<Duration>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        let hash_val = self.hash(key);
+        self.with_node(hash_val, key, |node, _| node.ttl(hash_val, key))
+    }
+
+    /// Removes `key` from the cache.
+    ///
+    /// # Returns
+    /// The removed value, if the key was present.
+    pub fn remove<Q>(&self, key: &Q) -> Option<V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        let hash_val = self.hash(key);
+        self.with_node(hash_val, key, |node, _| node.delete(hash_val, key).map(|(_, value)| value))
+    }
+
+    /// Returns the number of entries in the cache. Concurrent writes to other
+    /// nodes may already have changed it when it returns.
+    pub fn len(&self) -> usize {
+        self.shards().nodes.iter().map(|node| node.lock().unwrap().len()).sum()
+    }
+
+    /// Returns `true` if the cache holds no entries.
+    pub fn is_empty(&self) -> bool {
+        self.shards().nodes.iter().all(|node| node.lock().unwrap().is_empty())
+    }
+
+    /// Returns the number of nodes of the cache.
+    pub fn node_count(&self) -> usize {
+        self.shards().nodes.len()
+    }
+
+    /// Returns the maximum number of entries the cache can hold.
+    pub fn capacity(&self) -> usize {
+        self.node_count() * ARR_SIZE as usize
+    }
+
+    /// Removes every entry from the cache.
+    pub fn clear(&self) {
+        let shards = self.shards();
+        shards.nodes.iter().for_each(|node| *node.lock().unwrap() = self.factory.build());
+        let previous = shards.previous.read().unwrap();
+        if let Some(previous) = previous.as_ref() {
+            previous.nodes.iter().for_each(|node| *node.lock().unwrap() = self.factory.build());
+        }
+    }
+}
+
+/// Reports `hash_val` to `router` once every `RECORD_EVERY` accesses of the
+/// current thread on average. Only relative concentrations matter to the
+/// router, and sampling spares every access a write to its shared counters.
+#[inline(always)]
+fn record<R: Router>(router: &R, hash_val: u64) {
+    let sampled = SAMPLER.with(|state| {
+        let mut val = state.get();
+        val ^= val << 13;
+        val ^= val >> 7;
+        val ^= val << 17;
+        state.set(val);
+        val % RECORD_EVERY == 0
+    });
+    if sampled {
+        router.record(hash_val);
+    }
+}
+
+impl<K, V, R, P> Shards<K, V, R, P>
+where
+    K: Hash + Eq,
+    R: Router,
+    P: EvictionPolicy<K, V>,
+{
+    /// Creates the shards of `nodes`, indexed by `router`.
+    fn new(nodes: Vec<Node<K, V, P>>, router: R) -> Self {
+        Shards {
+            nodes: nodes.into_iter().map(|node| Arc::new(Mutex::new(node))).collect(),
+            router,
+            moving: AtomicBool::new(false),
+            previous: RwLock::new(None),
+        }
+    }
+
+    /// Locks the node owning `key`, after moving its entry there if it is still
+    /// in the node it lived in before the partitioning changed.
+    ///
+    /// The route is checked again once the node is locked: a rebalance may have
+    /// handed the key to another node meanwhile, which is then locked instead.
+    fn lock_owner<Q>(&self, hash_val: u64, key: &Q) -> NodeGuard<'_, K, V, P>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        loop {
+            let owner = self.router.route(hash_val);
+            if self.moving.load(Acquire) {
+                self.forward(hash_val, key, owner);
+            }
+            let node = self.nodes[owner].lock().unwrap();
+            if self.router.route(hash_val) == owner {
+                return node;
+            }
+        }
+    }
+
+    /// Moves the entry of `key` to `owner` from the node it lived in before the
+    /// partitioning changed, if it did not move yet.
+    fn forward<Q>(&self, hash_val: u64, key: &Q, owner: usize)
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        let previous = self.previous.read().unwrap();
+        let Some(previous) = previous.as_ref() else {
+            return;
+        };
+        let (from, to) = (&previous.nodes[previous.router.route(hash_val)], &self.nodes[owner]);
+        if Arc::ptr_eq(from, to) {
+            return;
+        }
+        let (mut from, mut to) = lock_pair(from, to);
+        if let Some((key, value, ttl)) = from.take(hash_val, key) {
+            adopt(&mut to, hash_val, key, value, ttl);
+        }
+    }
+
+    /// Keeps `previous` for operations to find the entries that did not move
+    /// yet. Called before the router publishes the new partitioning.
+    fn begin_move(&self, previous: Previous<K, V, R, P>) {
+        *self.previous.write().unwrap() = Some(previous);
+        self.moving.store(true, Release);
+    }
+
+    /// Drops the previous partitioning once every entry moved.
+    fn end_move(&self) {
+        self.moving.store(false, Release);
+        *self.previous.write().unwrap() = None;
+    }
+
+    /// Moves every entry of the `sources` nodes that the router now sends
+    /// elsewhere to the node now owning it. The destinations of a source are
+    /// listed first, then every pair of source and destination is locked in
+    /// turn, so that operations on other nodes go on.
+    ///
+    /// # Returns
+    /// The number of migrated entries.
+    fn migrate_from<S: BuildHasher>(&self, hasher: &S, sources: &[SharedNode<K, V, P>]) -> usize {
+        let rehash = |key: &K| hasher.hash_one(key);
+        let mut migrated = 0;
+        for from in sources {
+            let owners: BTreeSet<usize> = from
+                .lock()
+                .unwrap()
+                .hashes(rehash)
+                .map(|hash_val| self.router.route(hash_val))
+                .filter(|&owner| !Arc::ptr_eq(&self.nodes[owner], from))
+                .collect();
+            for owner in owners {
+                let (mut from, mut to) = lock_pair(from, &self.nodes[owner]);
+                for (hash_val, key, value, ttl) in from.drain_where(rehash, |hash_val| self.router.route(hash_val) == owner) {
+                    adopt(&mut to, hash_val, key, value, ttl);
+                    migrated += 1;
+                }
+            }
+        }
+        migrated
+    }
+}
+
+/// Locks two different nodes, always in the order of their addresses, so that
+/// threads locking the same pair never wait for each other.
+///
+/// # Returns
+/// The guards of `from` and `to`, in that order.
+fn lock_pair<'a, K, V, P>(
+    from: &'a SharedNode<K, V, P>,
+    to: &'a SharedNode<K, V, P>,
+) -> (NodeGuard<'a, K, V, P>, NodeGuard<'a, K, V, P>)
+where
+    K: Hash + Eq,
+    P: EvictionPolicy<K, V>,
+{
+    if Arc::as_ptr(from) < Arc::as_ptr(to) {
+        let from = from.lock().unwrap();
+        (from, to.lock().unwrap())
+    } else {
+        let to = to.lock().unwrap();
+        (from.lock().unwrap(), to)
+    }
+}
+
+/// Inserts an entry moved from another node into `node`, keeping what is left
+/// of its TTL, unless the key was written to `node` since.
+fn adopt<K, V, P>(node: &mut Node<K, V, P>, hash_val: u64, key: K, value: V, ttl: Option<Duration>)
+where
+    K: Hash + Eq,
+    P: EvictionPolicy<K, V>,
+{
+    if node.handle(hash_val, &key).is_none() {
+        let _ = node.try_insert(hash_val, key, value, ttl);
+    }
+}
@@ -0,0 +1,330 @@
+#[test]
+pub fn owl1_insert_get_remove(){
+    use super::owl::Owl;
+
+    let owl = Owl::<u64,u64>::new(4);
+    assert_eq!(owl.node_count(), 4);
+    assert_eq!(owl.capacity(), 4 * 65521);
+    for key in 0..100_000u64{
+        assert_eq!(owl.insert(key, key * 2), None);
+    }
+    assert_eq!(owl.len(), 100_000);
+    assert_eq!(owl.insert(7, 70), Some(14));
+    for key in 0..100_000u64{
+        assert!(owl.get(&key).is_some());
+    }
+    assert_eq!(owl.remove(&7), Some(70));
+    assert_eq!(owl.remove(&7), None);
+    assert_eq!(owl.get(&7), None);
+    assert_eq!(owl.len(), 99_999);
+}
+
+#[test]
+pub fn owl2_string_keys_and_clear(){
+    use super::owl::Owl;
+
+    let owl = Owl::<String,usize>::new(2);
+    for idx in 0..1000{
+        owl.insert(format!("key-{idx}"), idx);
+    }
+    assert_eq!(owl.get(&"key-500".to_string()), Some(500));
+    assert_eq!(owl.len(), 1000);
+    owl.clear();
+    assert!(owl.is_empty());
+    assert_eq!(owl.get(&"key-500".to_string()), None);
+}
+
+#[test]
+pub fn owl3_rebalance_migrates_entries(){
+    use std::hash::{BuildHasherDefault, Hasher};
+    use std::time::Duration;
+    use super::owl::Owl;
+
+    /// Uses the key as its own hash, so the test controls ring positions.
+    #[derive(Default)]
+    struct KeyHasher(u64);
+    impl Hasher for KeyHasher{
+        fn finish(&self) -> u64 { self.0 }
+        fn write(&mut self, _: &[u8]) { unreachable!() }
+        fn write_u64(&mut self, val: u64) { self.0 = val }
+    }
+
+    let owl = Owl::<u64,u64,_>::with_hasher(4, BuildHasherDefault::<KeyHasher>::default()).with_vnodes(2);
+    // Node 0 owns positions [0, 8192), every key below lands in its first half.
+    let keys: Vec<u64> = (0..20_000u64).map(|idx| ((idx % 4096) << 48) | idx).collect();
+    for &key in &keys{
+        owl.insert(key, key);
+    }
+    owl.insert_with_ttl(1 << 48, 0, Duration::from_secs(60));
+    let migrated = owl.rebalance();
+    assert!(migrated > 0);
+    assert_eq!(owl.len(), 20_001);
+    for &key in &keys{
+        assert_eq!(owl.get(&key), Some(key));
+    }
+    assert!(owl.ttl(&(1 << 48)).is_some());
+}
+
+#[test]
+pub fn owl4_split_and_merge(){
+    use super::owl::Owl;
+
+    let owl = Owl::<u64,u64>::new(1).with_node_limits(1, 4);
+    for key in 0..300_000u64{
+        owl.insert(key, key);
+    }
+    // The single node thrashed, so it was split and keeps more entries than one node could.
+    assert!(owl.node_count() > 1);
+    assert!(owl.len() > 65521);
+    for key in 299_000..300_000u64{
+        assert_eq!(owl.get(&key), Some(key));
+    }
+
+    // Once nearly empty, nodes merge back and keep what is left.
+    for key in 0..299_990u64{
+        owl.remove(&key);
+    }
+    owl.resize();
+    assert_eq!(owl.node_count(), 1);
+    assert_eq!(owl.len(), 10);
+    for key in 299_990..300_000u64{
+        assert_eq!(owl.get(&key), Some(key));
+    }
+}
+
+#[test]
+pub fn owl5_routers(){
+    use std::collections::hash_map::RandomState;
+    use super::owl::Owl;
+    use super::super::router::{JumpHash, Maglev, Rendezvous, Router};
+
+    /// Grows a one node cache past a node's capacity, then shrinks it back.
+    fn grow_and_shrink<R: Router>(router: R){
+        let owl = Owl::<u64,u64,_,R>::with_router(router, RandomState::new()).with_node_limits(1, 3);
+        for key in 0..200_000u64{
+            owl.insert(key, key);
+        }
+        assert!(owl.node_count() > 1);
+        for key in 199_000..200_000u64{
+            assert_eq!(owl.get(&key), Some(key));
+        }
+        for key in 0..199_990u64{
+            owl.remove(&key);
+        }
+        owl.resize();
+        assert_eq!((owl.node_count(), owl.len()), (1, 10));
+        for key in 199_990..200_000u64{
+            assert_eq!(owl.get(&key), Some(key));
+        }
+    }
+
+    grow_and_shrink(JumpHash::new(1));
+    grow_and_shrink(Rendezvous::new(1));
+    grow_and_shrink(Maglev::new(1));
+}
+
+#[test]
+pub fn owl6_shared_between_threads(){
+    use std::sync::Arc;
+    use std::thread;
+    use super::owl::Owl;
+
+    fn assert_send_sync<T: Send + Sync>(){}
+    assert_send_sync::<Owl<String,Vec<u8>>>();
+
+    let owl = Arc::new(Owl::<u64,u64>::new(4));
+    let workers: Vec<_> = (0..8u64).map(|worker| {
+        let owl = Arc::clone(&owl);
+        thread::spawn(move || {
+            for key in worker * 10_000..(worker + 1) * 10_000{
+                owl.insert(key, key);
+                assert_eq!(owl.get(&key).map(|value| value % 80_000), Some(key));
+                // Every worker also reads and overwrites keys of the others.
+                let other = (key + 10_000) % 80_000;
+                owl.get_with(&other, |&value| assert_eq!(value % 80_000, other));
+                if key % 7 == 0{
+                    owl.insert(other, other + 80_000);
+                }
+            }
+        })
+    }).collect();
+    for worker in workers{
+        worker.join().unwrap();
+    }
+    assert_eq!(owl.len(), 80_000);
+    for key in 0..80_000u64{
+        assert_eq!(owl.get(&key).map(|value| value % 80_000), Some(key));
+    }
+}
+
+#[test]
+pub fn owl7_entry_api(){
+    use std::sync::Arc;
+    use std::thread;
+    use super::owl::Owl;
+
+    // Concurrent read-modify-writes on shared counters lose no update.
+    let owl = Arc::new(Owl::<u64,u64>::new(4));
+    let workers: Vec<_> = (0..4).map(|_| {
+        let owl = Arc::clone(&owl);
+        thread::spawn(move || {
+            for key in 0..10_000u64{
+                owl.compute(key % 100, |count| Some(count.map_or(1, |count| count + 1)));
+            }
+        })
+    }).collect();
+    for worker in workers{
+        worker.join().unwrap();
+    }
+    for key in 0..100u64{
+        assert_eq!(owl.get(&key), Some(400));
+    }
+
+    assert_eq!(owl.get_or_insert_with(500, || 5), Some(5));
+    assert_eq!(owl.get_or_insert_with(500, || 6), Some(5));
+    assert!(owl.and_modify(&500, |value| *value *= 2));
+    assert!(!owl.and_modify(&501, |value| *value *= 2));
+    assert_eq!(owl.remove_if(&500, |&value| value == 5), None);
+    assert_eq!(owl.remove_if(&500, |&value| value == 10), Some(10));
+    assert_eq!(owl.compute(0, |_| None), None);
+    assert_eq!(owl.len(), 99);
+}
+
+#[test]
+pub fn owl8_borrowed_keys(){
+    use super::owl::Owl;
+
+    let owl = Owl::<String,u64>::new(4);
+    for key in 0..1000u64{
+        owl.insert(key.to_string(), key);
+    }
+    // `&str` lookups hash like the `String` keys and need no allocation.
+    assert_eq!(owl.get("42"), Some(42));
+    assert_eq!(owl.get_with("43", |&value| value * 2), Some(86));
+    assert_eq!(owl.get("1000"), None);
+    assert!(owl.and_modify("44", |value| *value = 0));
+    assert_eq!(owl.remove_if("44", |&value| value == 0), Some(0));
+    assert_eq!(owl.remove("45"), Some(45));
+    assert_eq!(owl.ttl("46"), None);
+    assert_eq!(owl.len(), 998);
+
+    let owl = Owl::<Vec<u8>,u64>::new(1);
+    owl.insert(b"owl".to_vec(), 1);
+    assert_eq!(owl.get(&b"owl"[..]), Some(1));
+}
+
+#[test]
+pub fn owl9_values_without_default(){
+    use std::fs::File;
+    use super::owl::Owl;
+
+    // Open files have no default, nor any order.
+    let owl = Owl::<(u32, String),File>::new(2);
+    owl.insert((1, String::from("manifest")), File::open("Cargo.toml").unwrap());
+    let len = owl.get_with(&(1, String::from("manifest")), |file| file.metadata().unwrap().len());
+    assert!(len.unwrap() > 0);
+    assert!(owl.remove(&(1, String::from("manifest"))).is_some());
+    assert!(owl.is_empty());
+}
+
+#[test]
+pub fn owl10_node_factory(){
+    use std::sync::Arc;
+    use std::time::Duration;
+    use super::owl::Owl;
+    use super::super::node::admission::TinyLfu;
+    use super::super::node::node::Node;
+    use super::super::node::policy::Sieve;
+    use super::super::node::weigher::Budget;
+
+    // Every node, including the ones rebuilt by `clear`, evicts with SIEVE
+    // behind an admission filter, and charges one budget shared by all nodes.
+    let budget = Arc::new(Budget::new(1000));
+    let shared = budget.clone();
+    let owl = Owl::<u64,Vec<u8>>::new(4).with_nodes(move || {
+        Node::<u64,Vec<u8>,Sieve>::default()
+            .with_admission(TinyLfu::new())
+            .with_weigher(|_: &u64, value: &Vec<u8>| value.len() as u32, shared.clone())
+            .with_tag_line()
+    });
+    for round in 0..2{
+        for key in 0..100u64{
+            owl.insert(key, vec![0; 100]);
+        }
+        // Nodes below their share of 250 are not starved by the others, which
+        // may take the total past the limit, though never past twice the limit.
+        assert!(owl.len() <= 20);
+        assert!(budget.used() <= 2000);
+        assert_eq!(owl.try_insert(1000, vec![0; 2000]), Err((1000, vec![0; 2000])));
+        if round == 0{
+            owl.clear();
+            assert_eq!(budget.used(), 0);
+        }
+    }
+
+    // Factory built nodes keep track of TTLs too.
+    owl.clear();
+    owl.insert_with_ttl(2000, vec![0; 10], Duration::from_secs(60));
+    assert!(owl.ttl(&2000).is_some());
+}
+
+#[test]
+pub fn owl11_rebalance_alongside_operations(){
+    use std::hash::{BuildHasherDefault, Hasher};
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicBool, Ordering::Relaxed};
+    use std::thread;
+    use super::owl::Owl;
+
+    /// Uses the key as its own hash, so the test controls ring positions.
+    #[derive(Default)]
+    struct KeyHasher(u64);
+    impl Hasher for KeyHasher{
+        fn finish(&self) -> u64 { self.0 }
+        fn write(&mut self, _: &[u8]) { unreachable!() }
+        fn write_u64(&mut self, val: u64) { self.0 = val }
+    }
+
+    let owl = Arc::new(Owl::<u64,u64,_>::with_hasher(4, BuildHasherDefault::<KeyHasher>::default()).with_vnodes(2));
+    let keys: Vec<u64> = (0..20_000u64).map(|idx| ((idx % 4096) << 48) | idx).collect();
+    for &key in &keys{
+        owl.insert(key, key);
+    }
+
+    // Workers keep writing and removing keys of the moving ranges while they move.
+    let done = Arc::new(AtomicBool::new(false));
+    let workers: Vec<_> = (1..5u64).map(|worker| {
+        let (owl, done) = (Arc::clone(&owl), Arc::clone(&done));
+        thread::spawn(move || {
+            let keys: Vec<u64> = (0..2_000u64).map(|idx| ((idx % 4096) << 48) | (worker << 32) | idx).collect();
+            let mut round = 0;
+            while !done.load(Relaxed) || round < 2{
+                for &key in &keys{
+                    owl.insert(key, key + round);
+                }
+                for &key in &keys{
+                    assert_eq!(owl.get(&key), Some(key + round));
+                    if key % 2 == 0{
+                        assert_eq!(owl.remove(&key), Some(key + round));
+                    }
+                }
+                // Removed entries are not brought back by the migration.
+                for &key in &keys{
+                    assert_eq!(owl.get(&key), (key % 2 == 1).then_some(key + round));
+                }
+                round += 1;
+            }
+        })
+    }).collect();
+    let migrated: usize = (0..4).map(|_| owl.rebalance()).sum();
+    done.store(true, Relaxed);
+    for worker in workers{
+        worker.join().unwrap();
+    }
+
+    assert!(migrated > 0);
+    assert_eq!(owl.len(), 20_000 + 4 * 1_000);
+    for &key in &keys{
+        assert_eq!(owl.get(&key), Some(key));
+    }
+}
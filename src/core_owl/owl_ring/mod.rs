@@ -1,20 +1,15 @@
 #![allow(unused)]
 
-//! This module demonstrates the initialization of a consistency ring
-//! using the `RingEntity` structure.
+//! The consistency ring routing every hash to the node owning it.
+//!
+//! Every cache owns its `Ring`. Routing reads an immutable, versioned
+//! `RingLayout`, while rebalances and resizes build the next layout on the side
+//! and publish it in one swap.
 
 mod ring_entity; // The `ring_entity` module contains the definition and implementation
                  // of the `RingEntity` structure, which is used to track hash concentrations.
+mod ring; // The `ring` module maps ring positions to the nodes owning them.
+mod test;
 
-use ring_entity::RingEntity; // Brings the `RingEntity` structure from the `ring_entity` module into scope.
-
-/// A static array representing the consistency ring.
-/// 
-/// # Details
-/// - The array is of size `u16::MAX + 1` (65,536 elements).
-/// - Each element is initialized to a new instance of `RingEntity` using its default constructor.
-/// - The `pub` modifier makes the array accessible from other modules.
-///
-/// # Usage
-/// The `RING` array is used to track the distribution of hashes across the consistency ring.
-pub static RING: [RingEntity; u16::MAX as usize + 1] = [RingEntity::new(); u16::MAX as usize + 1];
+pub use ring_entity::RingEntity;
+pub use ring::{Migration, Ring, RingArc, RingLayout, DEFAULT_VNODES, RING_SIZE};
@@ -0,0 +1,520 @@
+use std::sync::atomic::{AtomicPtr, Ordering::{AcqRel, Acquire}};
+use std::sync::{Arc, Mutex};
+
+use super::super::node::epoch::Collector;
+use super::ring_entity::RingEntity;
+
+/// Number of positions on the ring, one per value of the top 16 bits of a hash.
+pub const RING_SIZE: usize = u16::MAX as usize + 1;
+
+/// Virtual nodes per node of a ring built with `Ring::new`.
+pub const DEFAULT_VNODES: usize = 16;
+
+/// A contiguous range of ring positions owned by one node.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RingArc {
+    /// First position of the arc.
+    pub start: usize,
+    /// Position right after the arc.
+    pub end: usize,
+    /// Node owning the arc.
+    pub node: u16,
+}
+
+/// A range of ring positions handed from one node to another by a rebalance.
+/// The entries of the range must follow it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Migration {
+    /// First position of the range.
+    pub start: usize,
+    /// Position right after the range.
+    pub end: usize,
+    /// Node owning the range before the rebalance.
+    pub from: u16,
+    /// Node owning the range after the rebalance.
+    pub to: u16,
+}
+
+/// One published version of the ring: which node owns every position.
+///
+/// A layout never changes once published, readers can keep one as long as they
+/// need a consistent view, even while a newer version is being published.
+#[derive(Debug, Clone)]
+pub struct RingLayout {
+    /// Incremented by every published change.
+    version: u64,
+
+    /// Arcs sorted by position, covering the whole ring.
+    arcs: Vec<RingArc>,
+
+    /// Node owning every position of the ring.
+    owners: Box<[u16]>,
+
+    /// Number of nodes the ring is split between.
+    nodes: usize,
+}
+
+impl RingLayout {
+    /// Returns the version of the layout.
+    #[inline(always)]
+    pub fn version(&self) -> u64 {
+        self.version
+    }
+
+    /// Returns the number of nodes the ring is split between.
+    #[inline(always)]
+    pub fn nodes(&self) -> usize {
+        self.nodes
+    }
+
+    /// Returns the arcs of the ring, sorted by position.
+    pub fn arcs(&self) -> &[RingArc] {
+        &self.arcs
+    }
+
+    /// Returns the node owning `hash_val`.
+    #[inline(always)]
+    pub fn route(&self, hash_val: u64) -> usize {
+        self.owners[Ring::position(hash_val)] as usize
+    }
+
+    /// Returns the nodes owning an arc right before or after an arc of `node`.
+    pub fn neighbours(&self, node: usize) -> Vec<usize> {
+        let count = self.arcs.len();
+        let mut neighbours: Vec<usize> = (0..count)
+            .filter(|&arc| self.arcs[arc].node as usize == node)
+            .flat_map(|arc| [self.arcs[(arc + count - 1) % count].node, self.arcs[(arc + 1) % count].node])
+            .map(|neighbour| neighbour as usize)
+            .filter(|&neighbour| neighbour != node)
+            .collect();
+        neighbours.sort_unstable();
+        neighbours.dedup();
+        neighbours
+    }
+
+    /// Adds a node taking the second half of every arc of `node`, cut at the
+    /// concentration median. See `Ring::split`.
+    fn split(&mut self, node: usize, concentration: &[RingEntity]) -> Vec<Migration> {
+        if self.nodes >= RING_SIZE {
+            return Vec::new();
+        }
+        let to = self.nodes as u16;
+        let migrations: Vec<Migration> = self
+            .arcs
+            .iter()
+            .filter(|arc| arc.node as usize == node && arc.end - arc.start > 1)
+            .map(|arc| Migration {
+                start: median(concentration, arc.start, arc.end),
+                end: arc.end,
+                from: node as u16,
+                to,
+            })
+            .collect();
+        if !migrations.is_empty() {
+            for migration in &migrations {
+                self.owners[migration.start..migration.end].fill(to);
+            }
+            self.nodes += 1;
+            self.rebuild_arcs();
+        }
+        migrations
+    }
+
+    /// Hands every arc of `from` to `into` and renumbers the last node as
+    /// `from`. See `Ring::merge`.
+    fn merge(&mut self, from: usize, into: usize) -> Vec<Migration> {
+        assert!(from != into && from.max(into) < self.nodes, "cannot merge node {from} into node {into}");
+        let migrations: Vec<Migration> = self
+            .arcs
+            .iter()
+            .filter(|arc| arc.node as usize == from)
+            .map(|arc| Migration {
+                start: arc.start,
+                end: arc.end,
+                from: from as u16,
+                to: into as u16,
+            })
+            .collect();
+        let last = (self.nodes - 1) as u16;
+        for owner in self.owners.iter_mut() {
+            if *owner as usize == from {
+                *owner = into as u16;
+            }
+            if *owner == last {
+                *owner = from as u16;
+            }
+        }
+        self.nodes -= 1;
+        self.rebuild_arcs();
+        migrations
+    }
+
+    /// Hands the positions `start..end`, the head or the tail of an arc, to
+    /// `node`. The arc is cut in two, even if its new neighbour belongs to
+    /// `node` already, until the arcs are rebuilt.
+    fn assign(&mut self, start: usize, end: usize, node: u16) {
+        self.owners[start..end].fill(node);
+        let arc = self.arcs.partition_point(|arc| arc.end <= start);
+        let cut = RingArc { start, end, node };
+        if self.arcs[arc].start == start {
+            self.arcs[arc].start = end;
+            self.arcs.insert(arc, cut);
+        } else {
+            self.arcs[arc].end = start;
+            self.arcs.insert(arc + 1, cut);
+        }
+    }
+
+    /// Recomputes the arcs from the owner of every position.
+    fn rebuild_arcs(&mut self) {
+        self.arcs.clear();
+        let mut start = 0;
+        for position in 1..=RING_SIZE {
+            if position == RING_SIZE || self.owners[position] != self.owners[start] {
+                self.arcs.push(RingArc {
+                    start,
+                    end: position,
+                    node: self.owners[start],
+                });
+                start = position;
+            }
+        }
+    }
+}
+
+/// Consistent-hash ring mapping every position to the node owning it.
+///
+/// A hash lands on the position given by its top 16 bits, the node bucket inside
+/// a node is derived from the low bits, so both stay independent. Every node
+/// starts with `vnodes` arcs interleaved with those of the other nodes.
+///
+/// Every position counts the hashes routed through it in a `RingEntity`. When a
+/// node carries far more than its share of that concentration, `rebalance` cuts
+/// its hottest arc and hands part of it to the coldest node.
+///
+/// The number of nodes can change online: `split` hands half of a node's arcs to
+/// a new node, `merge` folds a node into another one.
+///
+/// The ring is shared by reference: lookups and counters only need `&self`.
+/// Changes are serialized among themselves, computed on a copy of the current
+/// `RingLayout` and published as its next version by swapping a pointer, so
+/// lookups take no lock. They pin an epoch instead, which keeps the layout they
+/// read alive until they are done.
+pub struct Ring {
+    /// The current layout, never null.
+    layout: AtomicPtr<Arc<RingLayout>>,
+
+    /// Drops replaced layouts once no lookup can still read them.
+    retired: Collector<Arc<RingLayout>>,
+
+    /// Hashes routed through every position, halved after every rebalance.
+    concentration: Box<[RingEntity]>,
+
+    /// Serializes the changes of the layout.
+    writer: Mutex<()>,
+}
+
+impl Ring {
+    /// Creates a ring of `nodes` nodes with up to `DEFAULT_VNODES` arcs each.
+    ///
+    /// # Arguments
+    /// * `nodes` - Number of nodes, between `1` and `RING_SIZE`.
+    pub fn new(nodes: usize) -> Self {
+        Self::with_vnodes(nodes, DEFAULT_VNODES.min(RING_SIZE / nodes.max(1)).max(1))
+    }
+
+    /// Creates a ring of `nodes` nodes with `vnodes` arcs each, all of equal length.
+    ///
+    /// # Arguments
+    /// * `nodes` - Number of nodes.
+    /// * `vnodes` - Number of virtual nodes, that is arcs, per node.
+    ///
+    /// # Panics
+    /// If there are no arcs at all, or more arcs than positions.
+    pub fn with_vnodes(nodes: usize, vnodes: usize) -> Self {
+        let count = nodes * vnodes;
+        assert!((1..=RING_SIZE).contains(&count), "a ring holds between 1 and {RING_SIZE} arcs, got {count}");
+        let mut layout = RingLayout {
+            version: 0,
+            arcs: Vec::new(),
+            owners: vec![0u16; RING_SIZE].into_boxed_slice(),
+            nodes,
+        };
+        for arc in 0..count {
+            let (start, end) = (arc * RING_SIZE / count, (arc + 1) * RING_SIZE / count);
+            layout.owners[start..end].fill((arc % nodes) as u16);
+        }
+        layout.rebuild_arcs();
+        Self::with_layout(layout, (0..RING_SIZE).map(|_| RingEntity::new()).collect())
+    }
+
+    /// Creates a ring publishing `layout`, with the given concentrations.
+    fn with_layout(layout: RingLayout, concentration: Box<[RingEntity]>) -> Self {
+        Ring {
+            layout: AtomicPtr::new(Box::into_raw(Box::new(Arc::new(layout)))),
+            retired: Collector::new(),
+            concentration,
+            writer: Mutex::new(()),
+        }
+    }
+
+    /// Runs `read` on the current layout, which cannot be dropped meanwhile.
+    #[inline(always)]
+    fn read<T>(&self, read: impl FnOnce(&Arc<RingLayout>) -> T) -> T {
+        let _pin = self.retired.pin();
+        // SAFETY: the pointer is never null, and a replaced layout is only
+        // dropped once every reader pinned before its replacement is gone.
+        read(unsafe { &*self.layout.load(Acquire) })
+    }
+
+    /// Returns the current layout, which stays valid and unchanged however
+    /// long it is kept.
+    pub fn snapshot(&self) -> Arc<RingLayout> {
+        self.read(Arc::clone)
+    }
+
+    /// Returns the version of the current layout.
+    pub fn version(&self) -> u64 {
+        self.read(|layout| layout.version)
+    }
+
+    /// Returns the number of nodes the ring is split between.
+    pub fn nodes(&self) -> usize {
+        self.read(|layout| layout.nodes)
+    }
+
+    /// Returns the position of `hash_val` on the ring.
+    #[inline(always)]
+    pub const fn position(hash_val: u64) -> usize {
+        (hash_val >> 48) as usize
+    }
+
+    /// Returns the node owning `hash_val` in the current layout.
+    #[inline(always)]
+    pub fn route(&self, hash_val: u64) -> usize {
+        self.read(|layout| layout.route(hash_val))
+    }
+
+    /// Counts one more hash routed through the position of `hash_val`.
+    #[inline(always)]
+    pub fn record(&self, hash_val: u64) {
+        self.concentration[Self::position(hash_val)].inc();
+    }
+
+    /// Returns the nodes owning an arc right before or after an arc of `node`.
+    pub fn neighbours(&self, node: usize) -> Vec<usize> {
+        self.snapshot().neighbours(node)
+    }
+
+    /// Returns the concentration of the positions `start..end`.
+    fn load(&self, start: usize, end: usize) -> u64 {
+        self.concentration[start..end].iter().map(|entity| entity.get() as u64).sum()
+    }
+
+    /// Returns the concentration carried by every node of `layout`.
+    fn loads_of(&self, layout: &RingLayout) -> Vec<u64> {
+        let mut loads = vec![0u64; layout.nodes];
+        for arc in &layout.arcs {
+            loads[arc.node as usize] += self.load(arc.start, arc.end);
+        }
+        loads
+    }
+
+    /// Returns the concentration carried by every node.
+    pub fn node_loads(&self) -> Vec<u64> {
+        self.loads_of(&self.snapshot())
+    }
+
+    /// Runs `change` on a copy of the current layout and publishes the result
+    /// as the next version, unless it moved nothing and kept the node count.
+    fn publish<F>(&self, change: F) -> Vec<Migration>
+    where
+        F: FnOnce(&mut RingLayout) -> Vec<Migration>,
+    {
+        let _writer = self.writer.lock().unwrap_or_else(|err| err.into_inner());
+        let mut layout = RingLayout::clone(&self.snapshot());
+        let nodes = layout.nodes;
+        let migrations = change(&mut layout);
+        if !migrations.is_empty() || layout.nodes != nodes {
+            layout.version += 1;
+            let replaced = self.layout.swap(Box::into_raw(Box::new(Arc::new(layout))), AcqRel);
+            // SAFETY: the replaced layout came from `Box::into_raw` and is no
+            // longer reachable by new readers.
+            self.retired.retire(unsafe { Box::from_raw(replaced) });
+            self.retired.collect();
+        }
+        migrations
+    }
+
+    /// Moves arc boundaries until no node carries more than `skew` times the
+    /// mean concentration, or no arc can be cut further. Concentrations are
+    /// halved afterwards, so later rebalances follow the workload as it shifts.
+    ///
+    /// Every step takes the hottest arc of the hottest node and hands its head
+    /// or its tail, worth at most half the gap between the two, to the coldest
+    /// node. A single hot position is never split.
+    ///
+    /// # Arguments
+    /// * `skew` - Tolerated ratio between the hottest node and the mean, above `1.0`.
+    ///
+    /// # Returns
+    /// The ranges that changed owner, whose entries must be migrated.
+    pub fn rebalance(&self, skew: f64) -> Vec<Migration> {
+        let migrations = self.publish(|layout| {
+            let mut migrations = Vec::new();
+            // Loads are kept up to date as ranges move, rather than summed over
+            // the whole ring again at every step.
+            let mut loads = self.loads_of(layout);
+            let total: u64 = loads.iter().sum();
+            for _ in 0..layout.nodes {
+                let hot = (0..layout.nodes).max_by_key(|&node| loads[node]).unwrap_or(0);
+                let cold = (0..layout.nodes).min_by_key(|&node| loads[node]).unwrap_or(0);
+                if total == 0 || hot == cold || loads[hot] as f64 <= skew * total as f64 / layout.nodes as f64 {
+                    break;
+                }
+
+                let arc = *layout
+                    .arcs
+                    .iter()
+                    .filter(|arc| arc.node as usize == hot)
+                    .max_by_key(|arc| self.load(arc.start, arc.end))
+                    .expect("a loaded node owns an arc");
+                let (start, end) = self.cut(arc, (loads[hot] - loads[cold]) / 2);
+                if start == end {
+                    break;
+                }
+                layout.assign(start, end, cold as u16);
+                let moved = self.load(start, end);
+                loads[hot] = loads[hot].saturating_sub(moved);
+                loads[cold] += moved;
+                migrations.push(Migration {
+                    start,
+                    end,
+                    from: hot as u16,
+                    to: cold as u16,
+                });
+            }
+            if !migrations.is_empty() {
+                layout.rebuild_arcs();
+            }
+            migrations
+        });
+        self.concentration.iter().for_each(RingEntity::halve);
+        migrations
+    }
+
+    /// Picks the head or the tail of `arc` worth the most concentration without
+    /// going over `target`, always leaving at least one position to the arc.
+    ///
+    /// # Returns
+    /// The range to hand over, empty if nothing fits.
+    fn cut(&self, arc: RingArc, target: u64) -> (usize, usize) {
+        let (mut head, mut head_load) = (arc.start, 0);
+        while head + 1 < arc.end {
+            let load = head_load + self.concentration[head].get() as u64;
+            if load > target {
+                break;
+            }
+            (head, head_load) = (head + 1, load);
+        }
+        let (mut tail, mut tail_load) = (arc.end, 0);
+        while tail > arc.start + 1 {
+            let load = tail_load + self.concentration[tail - 1].get() as u64;
+            if load > target {
+                break;
+            }
+            (tail, tail_load) = (tail - 1, load);
+        }
+        match (head_load, tail_load) {
+            (0, 0) => (arc.start, arc.start),
+            (head_load, tail_load) if head_load > tail_load => (arc.start, head),
+            _ => (tail, arc.end),
+        }
+    }
+
+    /// Adds a node, taking the second half of every arc of `node`. Arcs are cut
+    /// at their concentration median, or in their middle when never hit.
+    ///
+    /// # Returns
+    /// The ranges handed to the new node, whose id is the previous node count.
+    /// Empty, and no node is added, if every arc of `node` is a single position
+    /// or the ring is already split between `RING_SIZE` nodes.
+    pub fn split(&self, node: usize) -> Vec<Migration> {
+        self.publish(|layout| layout.split(node, &self.concentration))
+    }
+
+    /// Removes `from`, handing all its arcs to `into`. The last node then takes
+    /// the id of `from`, so ids stay dense.
+    ///
+    /// # Returns
+    /// The ranges handed to `into`, expressed with the ids from before the merge.
+    ///
+    /// # Panics
+    /// If `from` and `into` are the same node, or either does not exist.
+    pub fn merge(&self, from: usize, into: usize) -> Vec<Migration> {
+        self.publish(|layout| layout.merge(from, into))
+    }
+
+    /// Returns a new ring with the current layout and concentrations, grown to
+    /// `nodes` nodes by splitting the node owning the most positions, or shrunk
+    /// by merging the last node into one of its neighbours.
+    pub fn resized(&self, nodes: usize) -> Self {
+        let mut layout = RingLayout::clone(&self.snapshot());
+        while layout.nodes < nodes {
+            let widest = (0..layout.nodes)
+                .max_by_key(|&node| layout.arcs.iter().filter(|arc| arc.node as usize == node).map(|arc| arc.end - arc.start).sum::<usize>())
+                .unwrap_or(0);
+            if layout.split(widest, &self.concentration).is_empty() {
+                break;
+            }
+        }
+        while layout.nodes > nodes.max(1) {
+            let last = layout.nodes - 1;
+            let into = layout.neighbours(last).first().copied().unwrap_or(0);
+            layout.merge(last, into);
+        }
+        layout.version += 1;
+        Self::with_layout(layout, self.concentration_copy())
+    }
+
+    /// Returns a copy of the concentration counters.
+    fn concentration_copy(&self) -> Box<[RingEntity]> {
+        self.concentration.iter().map(|entity| {
+            let copy = RingEntity::new();
+            copy.set(entity.get());
+            copy
+        }).collect()
+    }
+}
+
+impl Clone for Ring {
+    /// Returns a ring with the current layout and concentrations.
+    fn clone(&self) -> Self {
+        Self::with_layout(RingLayout::clone(&self.snapshot()), self.concentration_copy())
+    }
+}
+
+impl Drop for Ring {
+    /// Drops the current layout, replaced ones are dropped with the collector.
+    fn drop(&mut self) {
+        // SAFETY: the pointer came from `Box::into_raw` and nothing reads it anymore.
+        drop(unsafe { Box::from_raw(*self.layout.get_mut()) });
+    }
+}
+
+/// Returns the position splitting `start..end` into two halves of equal
+/// concentration, both of them non empty.
+fn median(concentration: &[RingEntity], start: usize, end: usize) -> usize {
+    let half: u64 = concentration[start..end].iter().map(|entity| entity.get() as u64).sum::<u64>() / 2;
+    if half == 0 {
+        return start + (end - start) / 2;
+    }
+    let mut load = 0;
+    for (position, entity) in concentration.iter().enumerate().take(end - 1).skip(start) {
+        load += entity.get() as u64;
+        if load >= half {
+            return position + 1;
+        }
+    }
+    end - 1
+}
@@ -1,9 +1,14 @@
+use std::sync::atomic::{AtomicU32, Ordering::Relaxed};
+
 /// Represents an entity within the consistency ring, 
 /// tracking the number of hashes pointing to a specific area (concentration).
-#[derive(Clone,Copy)]
+///
+/// The counter is atomic, so it can be bumped through a shared reference by
+/// every reader of the ring.
+#[derive(Debug)]
 pub struct RingEntity {
     /// The number of hashes pointing to this specific area.
-    concentration: u32,
+    concentration: AtomicU32,
 }
 
 impl RingEntity {
@@ -14,15 +19,21 @@ impl RingEntity {
     #[inline(always)]
     pub const fn new() -> Self {
         RingEntity {
-            concentration: 0,
+            concentration: AtomicU32::new(0),
         }
     }
 
     /// Increments the concentration value by 1, 
     /// signifying an additional hash now points/hits to this area.
     #[inline(always)]
-    pub fn inc(&mut self) {
-        self.concentration += 1;
+    pub fn inc(&self) {
+        self.concentration.fetch_add(1, Relaxed);
+    }
+
+    /// Halves the concentration value, so old hits weigh less than recent ones.
+    #[inline(always)]
+    pub fn halve(&self) {
+        self.set(self.get() >> 1);
     }
 
     /// Returns the current concentration value.
@@ -31,7 +42,7 @@ impl RingEntity {
     /// The number of hashes currently pointing to this area.
     #[inline(always)]
     pub fn get(&self) -> u32 {
-        self.concentration
+        self.concentration.load(Relaxed)
     }
 
     /// Sets the concentration value to the specified number.
@@ -39,7 +50,13 @@ impl RingEntity {
     /// # Arguments
     /// * `val` - The new concentration value indicating the number of hashes for this area.
     #[inline(always)]
-    pub fn set(&mut self, val: u32) {
-        self.concentration = val;
+    pub fn set(&self, val: u32) {
+        self.concentration.store(val, Relaxed);
+    }
+}
+
+impl Default for RingEntity {
+    fn default() -> Self {
+        Self::new()
     }
 }
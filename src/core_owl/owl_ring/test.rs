@@ -0,0 +1,143 @@
+use super::{Ring, RING_SIZE};
+
+#[test]
+pub fn ring1_vnodes_interleave(){
+    let ring = Ring::with_vnodes(3, 4);
+    assert_eq!(ring.snapshot().arcs().len(), 12);
+    assert_eq!(ring.route(0), 0);
+    assert_eq!(ring.route(u64::MAX), 2);
+    // Arcs alternate between nodes and are of equal length.
+    for pair in ring.snapshot().arcs().windows(2){
+        assert_ne!(pair[0].node, pair[1].node);
+        assert_eq!(pair[0].end, pair[1].start);
+        assert!((pair[0].end - pair[0].start).abs_diff(RING_SIZE / 12) <= 1);
+    }
+    let mut counts = [0usize; 3];
+    for position in 0..RING_SIZE as u64{
+        counts[ring.route(position << 48)] += 1;
+    }
+    assert!(counts.iter().all(|&count| count.abs_diff(RING_SIZE / 3) <= 4));
+}
+
+#[test]
+pub fn ring2_rebalance_moves_hot_arc(){
+    let ring = Ring::with_vnodes(4, 2);
+    // Node 0 owns [0, 8192), make the first 4096 positions hot.
+    for position in 0..4096u64{
+        for _ in 0..10{
+            ring.record(position << 48);
+        }
+    }
+    for position in 8192..RING_SIZE as u64{
+        ring.record(position << 48);
+    }
+    let before = ring.node_loads();
+    let migrations = ring.rebalance(1.5);
+    assert!(!migrations.is_empty());
+    assert!(migrations.iter().all(|migration| migration.from == 0));
+    for migration in &migrations{
+        assert!((migration.start..migration.end).all(|position| ring.route((position as u64) << 48) == migration.to as usize));
+    }
+
+    // The same concentration, measured on the new arcs, is spread more evenly.
+    let mut loads = [0u64; 4];
+    for position in 0..RING_SIZE as u64{
+        let hits = if position < 4096 { 10 } else if position >= 8192 { 1 } else { 0 };
+        loads[ring.route(position << 48)] += hits;
+    }
+    let spread = |loads: &[u64]| loads.iter().max().unwrap() - loads.iter().min().unwrap();
+    assert!(spread(&loads) < spread(&before));
+    assert!(loads[0] < before[0]);
+
+    // Concentrations were halved, an even ring is left alone.
+    let even = Ring::with_vnodes(4, 2);
+    for position in 0..RING_SIZE as u64{
+        even.record(position << 48);
+    }
+    assert!(even.rebalance(1.5).is_empty());
+}
+
+#[test]
+pub fn ring3_split_and_merge(){
+    let ring = Ring::with_vnodes(2, 2);
+    let migrations = ring.split(0);
+    assert_eq!(ring.nodes(), 3);
+    assert_eq!(migrations.len(), 2);
+    for migration in &migrations{
+        assert_eq!((migration.from, migration.to), (0, 2));
+        assert_eq!(migration.end - migration.start, RING_SIZE / 8);
+        assert_eq!(ring.route((migration.start as u64) << 48), 2);
+    }
+    assert!(ring.neighbours(2).contains(&0));
+
+    // Folding node 0 into node 1 renumbers node 2 as node 0.
+    ring.merge(0, 1);
+    assert_eq!(ring.nodes(), 2);
+    for migration in &migrations{
+        assert_eq!(ring.route((migration.start as u64) << 48), 0);
+        assert_eq!(ring.route(((migration.start - 1) as u64) << 48), 1);
+    }
+    assert!(ring.snapshot().arcs().iter().all(|arc| (arc.node as usize) < 2));
+}
+
+#[test]
+pub fn ring4_versions_and_shared_reads(){
+    use std::sync::Arc;
+
+    let ring = Arc::new(Ring::with_vnodes(2, 4));
+    let before = ring.snapshot();
+    assert_eq!(before.version(), 0);
+
+    // Readers keep routing and counting through `&Ring` while a split is published.
+    let readers: Vec<_> = (0..4u64).map(|reader| {
+        let ring = ring.clone();
+        std::thread::spawn(move || {
+            for position in 0..RING_SIZE as u64{
+                ring.record(position << 48);
+                assert!(ring.route((position << 48) | reader) < 3);
+            }
+        })
+    }).collect();
+    let migrations = ring.split(1);
+    readers.into_iter().for_each(|reader| reader.join().unwrap());
+
+    assert!(!migrations.is_empty());
+    assert_eq!(ring.version(), 1);
+    assert_eq!(ring.nodes(), 3);
+    // The old layout is untouched and still routes the moved ranges to node 1.
+    assert_eq!(before.nodes(), 2);
+    assert_eq!(before.route((migrations[0].start as u64) << 48), 1);
+    assert_eq!(ring.route((migrations[0].start as u64) << 48), 2);
+    assert_eq!(ring.node_loads().iter().sum::<u64>(), 4 * RING_SIZE as u64);
+
+    // Two rings are fully independent.
+    let other = Ring::with_vnodes(2, 4);
+    assert_eq!((other.version(), other.nodes()), (0, 2));
+}
+
+#[test]
+pub fn ring5_layouts_retired_under_readers(){
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicBool, Ordering::Relaxed};
+
+    let ring = Arc::new(Ring::with_vnodes(4, 4));
+    let done = Arc::new(AtomicBool::new(false));
+    // Readers route without a lock while replaced layouts are being dropped.
+    let readers: Vec<_> = (0..4u64).map(|reader| {
+        let (ring, done) = (ring.clone(), done.clone());
+        std::thread::spawn(move || {
+            let mut position = reader;
+            while !done.load(Relaxed){
+                position = (position + 7) % RING_SIZE as u64;
+                assert!(ring.route(position << 48) < 5);
+            }
+        })
+    }).collect();
+    for _ in 0..200{
+        assert!(!ring.split(0).is_empty());
+        ring.merge(4, 0);
+    }
+    done.store(true, Relaxed);
+    readers.into_iter().for_each(|reader| reader.join().unwrap());
+    assert_eq!((ring.version(), ring.nodes()), (400, 4));
+}
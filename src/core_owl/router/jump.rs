@@ -0,0 +1,62 @@
+use super::Router;
+
+/// Jump consistent hash (Lamping and Veach).
+///
+/// Stateless and allocation free: a lookup takes `O(log n)` steps and adding a
+/// node only moves the `1 / n` of the keys that land on it. Nodes can only be
+/// added or removed at the end, so `merge` reshuffles the node renumbered in
+/// place of the removed one.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct JumpHash {
+    /// Number of buckets.
+    nodes: usize,
+}
+
+impl JumpHash {
+    /// Creates a router over `nodes` nodes.
+    ///
+    /// # Panics
+    /// If `nodes` is `0`.
+    pub fn new(nodes: usize) -> Self {
+        assert!(nodes >= 1, "a router needs at least one node");
+        JumpHash { nodes }
+    }
+}
+
+impl Router for JumpHash {
+    #[inline(always)]
+    fn nodes(&self) -> usize {
+        self.nodes
+    }
+
+    #[inline(always)]
+    fn route(&self, hash_val: u64) -> usize {
+        let (mut key, mut bucket, mut next) = (hash_val, 0i64, 0i64);
+        while next < self.nodes as i64 {
+            bucket = next;
+            key = key.wrapping_mul(2_862_933_555_777_941_757).wrapping_add(1);
+            next = ((bucket + 1) as f64 * ((1i64 << 31) as f64 / ((key >> 33) + 1) as f64)) as i64;
+        }
+        bucket as usize
+    }
+
+    fn resized(&self, nodes: usize) -> Self {
+        Self::new(nodes)
+    }
+
+    /// The new node takes keys from every node.
+    fn split(&mut self, _node: usize) -> Vec<usize> {
+        self.nodes += 1;
+        (0..self.nodes - 1).collect()
+    }
+
+    /// The last bucket is dropped and its keys spread over the others. They are
+    /// stored in the node renumbered as `from`, which is reshuffled.
+    fn merge(&mut self, from: usize, _into: usize) -> Vec<usize> {
+        self.nodes -= 1;
+        match from < self.nodes {
+            true => vec![from],
+            false => Vec::new(),
+        }
+    }
+}
@@ -0,0 +1,111 @@
+use super::{mix, node_seed, Router};
+
+/// Number of entries of the lookup table, a prime.
+const TABLE_SIZE: usize = 65_537;
+
+/// Maglev hashing (Eisenbud et al.).
+///
+/// Every node fills the entries of a prime sized lookup table following its own
+/// permutation, in turns, so nodes get an almost equal share and a lookup is a
+/// single table read. Changing the node count rebuilds the table, which moves
+/// slightly more keys than the strict minimum. Balance degrades past a few
+/// hundred nodes.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Maglev {
+    /// Seed of every node.
+    seeds: Vec<u64>,
+
+    /// Nodes created so far, used to draw the next seed.
+    created: u64,
+
+    /// Node owning every entry.
+    table: Box<[u16]>,
+}
+
+impl Maglev {
+    /// Creates a router over `nodes` nodes.
+    ///
+    /// # Panics
+    /// If `nodes` is `0` or greater than `u16::MAX`.
+    pub fn new(nodes: usize) -> Self {
+        assert!((1..=u16::MAX as usize).contains(&nodes), "a maglev table holds between 1 and {} nodes", u16::MAX);
+        let mut maglev = Maglev {
+            seeds: (0..nodes as u64).map(node_seed).collect(),
+            created: nodes as u64,
+            table: Box::default(),
+        };
+        maglev.populate();
+        maglev
+    }
+
+    /// Fills the lookup table, every node taking in turn the next free entry of
+    /// its permutation.
+    fn populate(&mut self) {
+        let size = TABLE_SIZE as u64;
+        let permutations: Vec<(u64, u64)> = self
+            .seeds
+            .iter()
+            .map(|&seed| (mix(seed) % size, mix(seed ^ 0x5555_5555_5555_5555) % (size - 1) + 1))
+            .collect();
+        let mut next = vec![0u64; self.seeds.len()];
+        let mut table = vec![u16::MAX; TABLE_SIZE];
+        let mut filled = 0;
+        'fill: loop {
+            for (node, &(offset, skip)) in permutations.iter().enumerate() {
+                let mut entry = (offset + next[node] * skip) % size;
+                while table[entry as usize] != u16::MAX {
+                    next[node] += 1;
+                    entry = (offset + next[node] * skip) % size;
+                }
+                table[entry as usize] = node as u16;
+                next[node] += 1;
+                filled += 1;
+                if filled == TABLE_SIZE {
+                    break 'fill;
+                }
+            }
+        }
+        self.table = table.into_boxed_slice();
+    }
+}
+
+impl Router for Maglev {
+    #[inline(always)]
+    fn nodes(&self) -> usize {
+        self.seeds.len()
+    }
+
+    #[inline(always)]
+    fn route(&self, hash_val: u64) -> usize {
+        self.table[((hash_val >> 16) % TABLE_SIZE as u64) as usize] as usize
+    }
+
+    fn resized(&self, nodes: usize) -> Self {
+        let mut resized = self.clone();
+        while resized.seeds.len() < nodes {
+            resized.seeds.push(node_seed(resized.created));
+            resized.created += 1;
+        }
+        resized.seeds.truncate(nodes.max(1));
+        resized.populate();
+        resized
+    }
+
+    /// The rebuilt table moves entries between all nodes.
+    fn split(&mut self, _node: usize) -> Vec<usize> {
+        if self.seeds.len() >= u16::MAX as usize {
+            return Vec::new();
+        }
+        self.seeds.push(node_seed(self.created));
+        self.created += 1;
+        self.populate();
+        (0..self.seeds.len() - 1).collect()
+    }
+
+    /// The rebuilt table moves entries between all nodes.
+    fn merge(&mut self, from: usize, _into: usize) -> Vec<usize> {
+        self.seeds.swap_remove(from);
+        self.populate();
+        (0..self.seeds.len()).collect()
+    }
+}
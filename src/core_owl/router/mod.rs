@@ -0,0 +1,90 @@
+//! Strategies mapping a key's hash to the node owning it.
+//!
+//! An `Owl` is generic over its `Router`. The arc based `Ring` is the default
+//! and the only one balancing itself on hit concentration; `JumpHash`,
+//! `Rendezvous` and `Maglev` trade that for less memory, no shared state, or
+//! faster lookups. `Router::moved_keys` measures how much of the cache each of
+//! them reshuffles when the node count changes.
+
+mod ring;
+mod jump;
+mod rendezvous;
+mod maglev;
+#[cfg(test)]
+mod test;
+
+pub use jump::JumpHash;
+pub use rendezvous::Rendezvous;
+pub use maglev::Maglev;
+
+/// Maps hashes to node ids, which are always dense, from `0` to `nodes() - 1`.
+///
+/// Structural changes return the nodes whose entries may no longer be routed
+/// to them; the cache drains those nodes of every entry routed elsewhere, and
+/// keeps a clone of the router from before the change to find the entries that
+/// did not move yet.
+pub trait Router: Clone {
+    /// Returns the number of nodes.
+    fn nodes(&self) -> usize;
+
+    /// Returns the node owning `hash_val`.
+    fn route(&self, hash_val: u64) -> usize;
+
+    /// Returns a router for `nodes` nodes, derived from this one the way it
+    /// grows through `split` and shrinks through `merge`.
+    fn resized(&self, nodes: usize) -> Self;
+
+    /// Counts one more access of `hash_val`. Only routers balancing themselves
+    /// on the workload use it.
+    #[inline(always)]
+    fn record(&self, _hash_val: u64) {}
+
+    /// Moves load away from nodes carrying more than `skew` times their share,
+    /// while other threads keep routing hashes.
+    ///
+    /// # Returns
+    /// The nodes that lost part of their range.
+    fn rebalance(&self, _skew: f64) -> Vec<usize> {
+        Vec::new()
+    }
+
+    /// Adds a node, relieving `node` at least.
+    ///
+    /// # Returns
+    /// The nodes that lost part of their range, empty if no node was added.
+    fn split(&mut self, node: usize) -> Vec<usize>;
+
+    /// Removes `from`, preferably handing its range to `into`. The last node
+    /// takes the id of `from`.
+    ///
+    /// # Returns
+    /// The nodes, with their ids from after the merge, that lost part of their
+    /// range, besides `from` whose entries all have to move.
+    fn merge(&mut self, from: usize, into: usize) -> Vec<usize>;
+
+    /// Returns the nodes `node` is best merged into.
+    fn neighbours(&self, node: usize) -> Vec<usize> {
+        (0..self.nodes()).filter(|&other| other != node).collect()
+    }
+
+    /// Counts how many of `hashes` would be routed to another node if the node
+    /// count changed to `nodes`.
+    fn moved_keys(&self, nodes: usize, hashes: &[u64]) -> usize {
+        let resized = self.resized(nodes);
+        hashes.iter().filter(|&&hash_val| self.route(hash_val) != resized.route(hash_val)).count()
+    }
+}
+
+/// SplitMix64 finalizer, spreads node seeds and hashes over all 64 bits.
+#[inline(always)]
+fn mix(mut val: u64) -> u64 {
+    val = (val ^ (val >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    val = (val ^ (val >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    val ^ (val >> 31)
+}
+
+/// Returns the seed of the `idx`-th node ever created by a seeded router.
+#[inline(always)]
+fn node_seed(idx: u64) -> u64 {
+    mix(idx.wrapping_add(0x9E37_79B9_7F4A_7C15))
+}
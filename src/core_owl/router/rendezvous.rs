@@ -0,0 +1,63 @@
+use super::{mix, node_seed, Router};
+
+/// Rendezvous, or highest random weight, hashing.
+///
+/// Every key goes to the node with the highest score `mix(hash ^ seed)`. Nodes
+/// are identified by their seed, not their id, so any node can be removed and
+/// only its own keys move. Lookups are `O(n)`, which suits small node counts.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Rendezvous {
+    /// Seed of every node.
+    seeds: Vec<u64>,
+
+    /// Nodes created so far, used to draw the next seed.
+    created: u64,
+}
+
+impl Rendezvous {
+    /// Creates a router over `nodes` nodes.
+    ///
+    /// # Panics
+    /// If `nodes` is `0`.
+    pub fn new(nodes: usize) -> Self {
+        assert!(nodes >= 1, "a router needs at least one node");
+        Rendezvous {
+            seeds: (0..nodes as u64).map(node_seed).collect(),
+            created: nodes as u64,
+        }
+    }
+}
+
+impl Router for Rendezvous {
+    #[inline(always)]
+    fn nodes(&self) -> usize {
+        self.seeds.len()
+    }
+
+    #[inline(always)]
+    fn route(&self, hash_val: u64) -> usize {
+        (0..self.seeds.len()).max_by_key(|&node| mix(hash_val ^ self.seeds[node])).unwrap_or(0)
+    }
+
+    fn resized(&self, nodes: usize) -> Self {
+        let mut resized = self.clone();
+        while resized.seeds.len() < nodes {
+            resized.split(0);
+        }
+        resized.seeds.truncate(nodes.max(1));
+        resized
+    }
+
+    /// The new node takes keys from every node.
+    fn split(&mut self, _node: usize) -> Vec<usize> {
+        self.seeds.push(node_seed(self.created));
+        self.created += 1;
+        (0..self.seeds.len() - 1).collect()
+    }
+
+    /// Keys of `from` go to their next best node, `into` is not needed.
+    fn merge(&mut self, from: usize, _into: usize) -> Vec<usize> {
+        self.seeds.swap_remove(from);
+        Vec::new()
+    }
+}
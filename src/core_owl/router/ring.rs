@@ -0,0 +1,50 @@
+use super::super::owl_ring::{Migration, Ring};
+use super::Router;
+
+/// Returns the distinct source nodes of `migrations`.
+fn sources(migrations: Vec<Migration>) -> Vec<usize> {
+    let mut sources: Vec<usize> = migrations.into_iter().map(|migration| migration.from as usize).collect();
+    sources.sort_unstable();
+    sources.dedup();
+    sources
+}
+
+impl Router for Ring {
+    #[inline(always)]
+    fn nodes(&self) -> usize {
+        Ring::nodes(self)
+    }
+
+    #[inline(always)]
+    fn route(&self, hash_val: u64) -> usize {
+        Ring::route(self, hash_val)
+    }
+
+    fn resized(&self, nodes: usize) -> Self {
+        Ring::resized(self, nodes)
+    }
+
+    #[inline(always)]
+    fn record(&self, hash_val: u64) {
+        Ring::record(self, hash_val)
+    }
+
+    fn rebalance(&self, skew: f64) -> Vec<usize> {
+        sources(Ring::rebalance(self, skew))
+    }
+
+    fn split(&mut self, node: usize) -> Vec<usize> {
+        sources(Ring::split(self, node))
+    }
+
+    /// Arcs of `from` all go to `into` and the renumbered node keeps its arcs,
+    /// so no other node loses anything.
+    fn merge(&mut self, from: usize, into: usize) -> Vec<usize> {
+        Ring::merge(self, from, into);
+        Vec::new()
+    }
+
+    fn neighbours(&self, node: usize) -> Vec<usize> {
+        Ring::neighbours(self, node)
+    }
+}
@@ -0,0 +1,74 @@
+use xxhash_rust::xxh3::xxh3_64;
+
+use super::super::owl_ring::Ring;
+use super::{JumpHash, Maglev, Rendezvous, Router};
+
+/// Hashes of `count` distinct keys.
+fn hashes(count: u64) -> Vec<u64> {
+    (0..count).map(|key| xxh3_64(&key.to_le_bytes())).collect()
+}
+
+/// Asserts every node of `router` gets its share of `hashes`, give or take `slack`.
+fn assert_balanced<R: Router>(router: &R, hashes: &[u64], slack: f64) {
+    let mut counts = vec![0usize; router.nodes()];
+    for &hash_val in hashes{
+        counts[router.route(hash_val)] += 1;
+    }
+    let share = hashes.len() as f64 / router.nodes() as f64;
+    for count in counts{
+        assert!((count as f64 - share).abs() <= share * slack, "{count} keys for a share of {share}");
+    }
+}
+
+#[test]
+pub fn router1_jump_hash(){
+    let hashes = hashes(100_000);
+    let router = JumpHash::new(10);
+    assert_balanced(&router, &hashes, 0.05);
+    // Growing to 11 nodes only moves the keys of the new node.
+    let moved = router.moved_keys(11, &hashes) as f64 / hashes.len() as f64;
+    assert!((moved - 1.0 / 11.0).abs() < 0.01);
+    let grown = router.resized(11);
+    assert!(hashes.iter().all(|&hash_val| grown.route(hash_val) == 10 || grown.route(hash_val) == router.route(hash_val)));
+}
+
+#[test]
+pub fn router2_rendezvous(){
+    let hashes = hashes(50_000);
+    let mut router = Rendezvous::new(8);
+    assert_balanced(&router, &hashes, 0.05);
+    let moved = router.moved_keys(9, &hashes) as f64 / hashes.len() as f64;
+    assert!((moved - 1.0 / 9.0).abs() < 0.01);
+
+    // Removing node 3 only moves its keys, node 7 keeps its keys as node 3.
+    // The keys of node 3 go to their second best node, which may be node 7.
+    let before: Vec<usize> = hashes.iter().map(|&hash_val| router.route(hash_val)).collect();
+    assert!(router.merge(3, 0).is_empty());
+    for (&hash_val, &node) in hashes.iter().zip(&before){
+        match node{
+            3 => {}
+            7 => assert_eq!(router.route(hash_val), 3),
+            node => assert_eq!(router.route(hash_val), node),
+        }
+    }
+}
+
+#[test]
+pub fn router3_maglev(){
+    let hashes = hashes(100_000);
+    let router = Maglev::new(8);
+    assert_balanced(&router, &hashes, 0.05);
+    let moved = router.moved_keys(9, &hashes) as f64 / hashes.len() as f64;
+    assert!((1.0 / 9.0 - 0.01..0.2).contains(&moved), "{moved}");
+}
+
+#[test]
+pub fn router4_ring(){
+    let hashes = hashes(100_000);
+    let ring = Ring::new(8);
+    assert_balanced(&ring, &hashes, 0.05);
+    // Growing splits the widest node in two, only half of its keys move.
+    let moved = ring.moved_keys(9, &hashes) as f64 / hashes.len() as f64;
+    assert!((moved - 1.0 / 16.0).abs() < 0.01, "{moved}");
+    assert_eq!(ring.resized(6).nodes(), 6);
+}
@@ -0,0 +1,6 @@
+#![feature(portable_simd)]
+
+pub mod core_owl;
+
+pub use core_owl::hash::XXBuildHasher;
+pub use core_owl::owl::owl::Owl;
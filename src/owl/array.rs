@@ -2,7 +2,7 @@
 
 use std::{
     alloc::{alloc, dealloc, Layout}, // For dynamic memory allocation and deallocation
-    mem::{forget, ManuallyDrop},                     // Prevents dropping of ownership
+    mem::forget,                     // Prevents dropping of ownership
     ptr::NonNull                     // Non-nullable pointer type
 };
 
@@ -57,20 +57,21 @@ impl<T: Default> Default for Array<T> {
 }
 
 impl<T> Array<T> {
-    /// Retrieves the value at a specific index.
+    /// Retrieves a reference to the value at a specific index, rather than a
+    /// bitwise copy that would be dropped twice.
     ///
     /// # Arguments
     /// * `idx` - Index of the value to retrieve.
     ///
     /// # Returns
-    /// The value at the given index.
+    /// A reference to the value at the given index.
     #[inline]
-    pub unsafe  fn get_unchecked(&self, idx: usize) -> ManuallyDrop<T> {
-        unsafe { ((self.ptr.add(idx)).as_ptr() as *mut ManuallyDrop<T>).read() } // Dereference and read the value
+    pub unsafe  fn get_unchecked(&self, idx: usize) -> &T {
+        unsafe { self.ptr.add(idx).as_ref() } // Dereference the value in place
     }
 
     #[inline]
-    pub unsafe fn get_mut(&self,idx: usize)->&mut T{
+    pub unsafe fn get_mut(&mut self,idx: usize)->&mut T{
         unsafe {
             self.ptr.add(idx).as_mut()
         }
@@ -1,6 +1,5 @@
 pub mod array;
 pub mod node;
-pub mod hash;
 
 pub mod node_components;
 mod node_impl;
\ No newline at end of file
@@ -2,7 +2,7 @@
 
 
 // Importing required traits and modules
-use std::{hash::Hash, mem::ManuallyDrop};
+use std::hash::Hash;
 use crate::owl::node_components::{
     DataLine, 
     HashTable, 
@@ -31,9 +31,9 @@ use std::sync::atomic::{
 /// A `Node` represents a caching mechanism using data lines, 
 /// hash tables, and an empty line for space management.
 /// Generic parameters:
-/// - `Key`: Must implement `Hash`, `Ord`, `PartialOrd`, `Eq`, and `Clone`.
-/// - `Val`: Must implement `Clone`.
-pub struct Node<Key:Hash+Ord+PartialOrd+Eq+PartialEq+Clone, Val:Clone> {
+/// - `Key`: Must implement `Hash` and `Eq`.
+/// - `Val`: Any value type.
+pub struct Node<Key:Hash+Eq, Val> {
     /// Stores the data in key-value pairs
     data_line: DataLine<Key,Val>,
 
@@ -52,8 +52,7 @@ pub struct Node<Key:Hash+Ord+PartialOrd+Eq+PartialEq+Clone, Val:Clone> {
 
 impl<K, V> Node<K, V>
 where 
-    K: Hash + Ord + PartialOrd + Eq + PartialEq + Clone,
-    V: Clone,
+    K: Hash + Eq,
 {
     /// Creates a new `Node` instance with initialized components
     pub fn new() -> Self {
@@ -72,8 +71,7 @@ where
 // Implementing the `NodeImpl` trait for the `Node` struct
 impl<K, V> NodeImpl<K, V> for Node<K, V>
 where 
-    K: Hash + Ord + PartialOrd + Eq + PartialEq + Clone,
-    V: Clone,
+    K: Hash + Eq,
 {
     /// Deletes a key-value pair from the node. To be implemented.
     fn delete(&mut self, key: &K) -> Option<(K, V)> {
@@ -92,7 +90,7 @@ where
         let hash_idx = hash_val as usize % MAP_SIZE;
 
         // Retrieve a new index from the empty line
-        let new_idx = match self.empty_line.pop() {
+        let idx = match self.empty_line.pop() {
             Some(idx) => idx, // Use the available index
             None => {
                 let tail = self.tail.load(Acquire);
@@ -100,13 +98,10 @@ where
                 let prev = data.link.prev;
                 todo!("implement auto chain and auto link");
                 self.tail.store(prev, Release);
-                ManuallyDrop::new(tail)
+                tail
             }
         };
 
-        // Safely convert the new index into a valid type
-        let idx = unsafe { std::mem::transmute(new_idx) };
-
         // Default values for link and chain components
         let link = Link::default();
         let chain = Link::default();
@@ -2,17 +2,16 @@ use std::hash::Hash;
 use crate::owl::array::Array;
 use crate::owl::node::NULL_IDX;
 use crate::owl::node_components::Entity;
-use std::mem::ManuallyDrop;
 
 use super::Link;
 
-pub struct DataLine<Key:Hash+Ord+PartialOrd+Eq+PartialEq+Clone,Val:Clone>{
+pub struct DataLine<Key:Hash+Eq,Val>{
     data:Array<Entity<Key,Val>>
 }
 
 
 impl<K,V> DataLine<K,V> 
-where K:Hash+Ord+PartialOrd+Eq+PartialEq+Clone,V:Clone{
+where K:Hash+Eq{
     pub fn new()->Self{
         let array = Array::<Entity<K,V>>::new(65523);
         Self { data: array }
@@ -22,7 +21,7 @@ where K:Hash+Ord+PartialOrd+Eq+PartialEq+Clone,V:Clone{
         self.data.as_ptr() 
     }
 
-    pub fn read(&self,idx:u16)->ManuallyDrop<Entity<K,V>>{
+    pub fn read(&self,idx:u16)->&Entity<K,V>{
         unsafe {
             self.data.get_unchecked(idx as usize)
         }
@@ -33,7 +32,7 @@ where K:Hash+Ord+PartialOrd+Eq+PartialEq+Clone,V:Clone{
         self.data.set_unchecked(data, idx as usize);
     }
 
-    pub fn get_mut(&self,idx:u16)->&mut Entity<K,V>{
+    pub fn get_mut(&mut self,idx:u16)->&mut Entity<K,V>{
         unsafe {
             self.data.get_mut(idx as usize)
         }
@@ -41,7 +40,7 @@ where K:Hash+Ord+PartialOrd+Eq+PartialEq+Clone,V:Clone{
 
     pub fn get_ref(&self,idx:u16)->&Entity<K,V>{
         unsafe {
-            self.data.get_mut(idx as usize)
+            self.data.get_unchecked(idx as usize)
         }
     }
 
@@ -1,6 +1,5 @@
 #![allow(unused)]
 
-use std::mem::ManuallyDrop;
 // Importing the custom `Array` implementation
 use crate::owl::array::Array;
 
@@ -80,7 +79,7 @@ impl EmptyLine {
         }
 
         // Safely push the value.
-        return Some(unsafe { self.push_unchecked(data)}) // Return the index where the value was inserted.
+        Some(unsafe { self.push_unchecked(data)}) // Return the index where the value was inserted.
     }
 
     /// Pops the last value from the array.
@@ -90,13 +89,13 @@ impl EmptyLine {
     ///
     /// # Safety
     /// Caller must ensure the array is not empty before calling this function.
-    pub fn pop(&mut self) -> Option<ManuallyDrop<u16>> {
+    pub fn pop(&mut self) -> Option<u16> {
         match self.len.load(SeqCst) {
-            0 =>{ return None;},
+            0 => None,
             x =>{
-                let result = unsafe { self.arr.get_unchecked(x) };
+                let result = unsafe { *self.arr.get_unchecked(x) };
                 self.len.fetch_sub(1, SeqCst);
-                return Some(result);
+                Some(result)
             }
         }
     }
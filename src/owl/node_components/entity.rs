@@ -1,32 +1,22 @@
 #![allow(unused)]
 
-use std::hint::spin_loop;
-use std::mem::ManuallyDrop;
 use std::hash::Hash;
 use std::ops::{
     Deref,
     DerefMut
 };
-use std::sync::atomic::{
-    AtomicBool,
-    Ordering::{
-        Release,
-        Acquire
-    }
-};
+use crate::core_owl::node::data_line::entity_lock::EntityLock;
 use crate::owl::node_components::Link;
 
 use crate::owl::node::NULL_IDX;
 
 pub struct EntityGuard<'a,K,V>
-where K:Hash+Ord+PartialOrd+Eq+PartialEq+Clone,V:Clone{
+where K:Hash+Eq{
     data:&'a mut Entity<K,V>
 }
 
-/// ### TODO convert SpinLock to RWlock
-
 pub struct Entity<K,V>
-where K:Hash+Ord+PartialOrd+Eq+PartialEq+Clone,V:Clone{
+where K:Hash+Eq{
     /// Key
     key:K,
 
@@ -36,8 +26,8 @@ where K:Hash+Ord+PartialOrd+Eq+PartialEq+Clone,V:Clone{
     /// /// Index of the entity in a DataLine.
     idx:u16,
 
-    /// Lock to manage concurrent access.
-    lock:AtomicBool,
+    /// Reader-writer lock to manage concurrent access.
+    lock:EntityLock,
 
     /// Link for LRU or MRU Cache.
     pub link:Link,
@@ -46,10 +36,10 @@ where K:Hash+Ord+PartialOrd+Eq+PartialEq+Clone,V:Clone{
     pub chain:Link
 }
 
-impl<K:Hash+Ord+PartialOrd+Eq+PartialEq+Clone,V:Clone> Entity<K,V> {
+impl<K:Hash+Eq,V> Entity<K,V> {
 
     pub const fn new(key:K,value:V,idx:u16,link:Link,chain:Link)->Self{
-        let lock = AtomicBool::new(false);
+        let lock = EntityLock::new();
         Entity{
             key,
             value,
@@ -97,10 +87,8 @@ impl<K:Hash+Ord+PartialOrd+Eq+PartialEq+Clone,V:Clone> Entity<K,V> {
     }
 
     #[inline]
-    fn read(&self)->ManuallyDrop<V>{
-        unsafe {
-            (&self.value as *const V as *const ManuallyDrop<V>).read()
-        }
+    fn read(&self)->&V{
+        &self.value
     }
 
     #[inline]
@@ -116,9 +104,7 @@ impl<K:Hash+Ord+PartialOrd+Eq+PartialEq+Clone,V:Clone> Entity<K,V> {
     // pub fn read_lock(&self)
 
     pub fn lock(&mut self)->EntityGuard<'_,K,V>{
-        while self.lock.swap(true, Acquire) {
-            spin_loop();
-        }
+        self.lock.write();
         EntityGuard { data: self }
     }
     // #[inline]
@@ -139,7 +125,7 @@ impl<K:Hash+Ord+PartialOrd+Eq+PartialEq+Clone,V:Clone> Entity<K,V> {
 
 
 impl<K,V> Deref for EntityGuard<'_,K,V>
-where K:Hash+Ord+PartialOrd+Eq+PartialEq+Clone,V:Clone{
+where K:Hash+Eq{
     type Target = Entity<K,V>;
     fn deref(&self) -> &Self::Target {
         self.data
@@ -147,15 +133,15 @@ where K:Hash+Ord+PartialOrd+Eq+PartialEq+Clone,V:Clone{
 }
 
 impl<K,V> DerefMut for EntityGuard<'_,K,V> 
-where K:Hash+Ord+PartialOrd+Eq+PartialEq+Clone,V:Clone{
+where K:Hash+Eq{
     fn deref_mut(&mut self) -> &mut Self::Target {
         self.data
     }
 }
 
 impl<K,V> Drop for EntityGuard<'_,K,V>
-where K:Hash+Ord+PartialOrd+Eq+PartialEq+Clone,V:Clone {
+where K:Hash+Eq{
     fn drop(&mut self) {
-        self.lock.store(false, Release);
+        self.lock.write_unlock();
     }
 }
\ No newline at end of file
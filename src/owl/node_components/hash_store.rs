@@ -29,7 +29,7 @@ impl HashTable{
         arr.simd_default(0xFFFF);
 
         HashTable{
-            meta:meta,
+            meta,
             hash:arr
         }
     }
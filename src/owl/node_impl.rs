@@ -2,7 +2,7 @@
 
 use std::hash::Hash;
 
-pub trait NodeImpl<K: Hash + Ord + PartialOrd + Eq + PartialEq, V> {
+pub trait NodeImpl<K: Hash + Eq, V> {
     /// Inserts a key-value pair into the node.
     fn insert(&mut self,hash_val:u64, key: K, value: V);
 